@@ -0,0 +1,540 @@
+use std::{
+    future::Future,
+    hint::black_box,
+    pin::pin,
+    task::{Context, Poll, Waker},
+};
+
+use casus::{Event, Waiter};
+use criterion::{criterion_group, criterion_main, Criterion};
+
+/// Polls `fut` once without a waker, which is all an already-completed wait needs
+fn poll_once<F: Future>(fut: F) -> Poll<F::Output> {
+    pin!(fut).poll(&mut Context::from_waker(Waker::noop()))
+}
+
+fn event_is_set(c: &mut Criterion) {
+    let mut group = c.benchmark_group("event_is_set");
+    let event = Event::new();
+    event.set().unwrap();
+    group.bench_function("atomic", |b| b.iter(|| black_box(&event).is_set()));
+    let event = locked::Event::new();
+    event.set();
+    group.bench_function("locked", |b| b.iter(|| black_box(&event).is_set()));
+    group.finish();
+}
+
+fn event_wait_set(c: &mut Criterion) {
+    let mut group = c.benchmark_group("event_wait_set");
+    let event = Event::new();
+    event.set().unwrap();
+    group.bench_function("atomic", |b| b.iter(|| poll_once(black_box(&event).wait())));
+    let event = locked::Event::new();
+    event.set();
+    group.bench_function("locked", |b| b.iter(|| poll_once(black_box(&event).wait())));
+    group.finish();
+}
+
+fn event_set_wait(c: &mut Criterion) {
+    let mut group = c.benchmark_group("event_set_wait");
+    let event = Event::new();
+    group.bench_function("atomic", |b| {
+        b.iter(|| {
+            let mut wait = pin!(event.wait());
+            let mut cx = Context::from_waker(Waker::noop());
+            let _ = wait.as_mut().poll(&mut cx);
+            event.set().unwrap();
+            let _ = wait.as_mut().poll(&mut cx);
+            event.clear().unwrap();
+        })
+    });
+    let event = locked::Event::new();
+    group.bench_function("locked", |b| {
+        b.iter(|| {
+            let mut wait = pin!(event.wait());
+            let mut cx = Context::from_waker(Waker::noop());
+            let _ = wait.as_mut().poll(&mut cx);
+            event.set();
+            let _ = wait.as_mut().poll(&mut cx);
+            event.clear();
+        })
+    });
+    group.finish();
+}
+
+fn waiter_poll_pending(c: &mut Criterion) {
+    let mut group = c.benchmark_group("waiter_poll_pending");
+    let (_handle, mut waiter) = Waiter::<u32>::new();
+    group.bench_function("atomic", |b| b.iter(|| poll_once(&mut waiter)));
+    let (_handle, mut waiter) = locked::Waiter::<u32>::new();
+    group.bench_function("locked", |b| b.iter(|| poll_once(&mut waiter)));
+    group.finish();
+}
+
+fn waiter_wake(c: &mut Criterion) {
+    let mut group = c.benchmark_group("waiter_wake");
+    group.bench_function("atomic", |b| {
+        b.iter(|| {
+            let (handle, waiter) = Waiter::new();
+            handle.wake(black_box(1)).unwrap();
+            poll_once(waiter)
+        })
+    });
+    group.bench_function("locked", |b| {
+        b.iter(|| {
+            let (handle, waiter) = locked::Waiter::new();
+            handle.wake(black_box(1)).unwrap();
+            poll_once(waiter)
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    event_is_set,
+    event_wait_set,
+    event_set_wait,
+    waiter_poll_pending,
+    waiter_wake
+);
+criterion_main!(benches);
+
+/// The lock-based `Event`, `Waiter` and `WaitList` from before they moved to atomics, copied from
+/// casus as they were apart from leaving out `SharedWaiter` and the timeout and blocking methods,
+/// so the benchmarks compare against the implementation that was actually replaced
+#[allow(dead_code)]
+mod locked {
+    pub use self::{event::Event, waiter::Waiter};
+
+    mod list {
+        use std::collections::VecDeque;
+
+        use super::waiter::{Waiter, WakeHandle};
+
+        /// A FIFO list of registered waiters, each identified by a key so that it can deregister
+        /// itself when the future waiting on it is dropped
+        #[derive(Debug)]
+        pub struct WaitList<T> {
+            next_key: u64,
+            entries: VecDeque<(u64, WakeHandle<T>)>,
+        }
+
+        impl<T> WaitList<T> {
+            pub fn new() -> Self {
+                Self {
+                    next_key: 0,
+                    entries: VecDeque::new(),
+                }
+            }
+
+            pub fn register(&mut self) -> (u64, Waiter<T>) {
+                let key = self.next_key;
+                self.next_key += 1;
+                let (handle, waiter) = Waiter::new();
+                self.entries.push_back((key, handle));
+                (key, waiter)
+            }
+
+            pub fn remove(&mut self, key: u64) -> bool {
+                // keys are handed out in increasing order, so the list is always sorted by key
+                match self.entries.binary_search_by_key(&key, |(k, _)| *k) {
+                    Ok(i) => {
+                        self.entries.remove(i);
+                        true
+                    },
+                    Err(_) => false,
+                }
+            }
+
+            pub fn wake_one(&mut self, mut v: T) -> bool {
+                while let Some((_, handle)) = self.entries.pop_front() {
+                    match handle.wake(v) {
+                        Ok(()) => return true,
+                        // the waiter was dropped without deregistering, so try the next one
+                        Err(returned) => v = returned,
+                    }
+                }
+                false
+            }
+
+            pub fn wake_all(&mut self, v: T)
+            where
+                T: Clone,
+            {
+                for (_, handle) in self.entries.drain(..) {
+                    let _ = handle.wake(v.clone());
+                }
+            }
+        }
+    }
+
+    mod event {
+        use std::sync::Mutex;
+
+        use super::{list::WaitList, waiter::Waiter};
+
+        #[derive(Debug)]
+        pub struct Event {
+            // the state and the waiters share a lock so that checking the state and registering a
+            // waiter can't interleave with `set` or `clear`
+            inner: Mutex<Inner>,
+            auto_reset: bool,
+        }
+
+        #[derive(Debug)]
+        struct Inner {
+            set: bool,
+            waiters: WaitList<Release>,
+        }
+
+        /// What released a waiter
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        enum Release {
+            Set,
+            Pulse,
+        }
+
+        impl Event {
+            pub fn new() -> Self {
+                Self {
+                    inner: Mutex::new(Inner {
+                        set: false,
+                        waiters: WaitList::new(),
+                    }),
+                    auto_reset: false,
+                }
+            }
+
+            pub fn auto_reset() -> Self {
+                Self {
+                    auto_reset: true,
+                    ..Self::new()
+                }
+            }
+
+            pub async fn wait(&self) -> bool {
+                let (key, waiter) = {
+                    let mut inner = self.inner.lock().unwrap();
+                    if inner.set {
+                        if self.auto_reset {
+                            inner.set = false;
+                        }
+                        return true;
+                    }
+                    inner.waiters.register()
+                };
+                let mut registration = Registration {
+                    event: self,
+                    key,
+                    waiter,
+                };
+                // the event holds on to the handle until it wakes the waiter, so it can't be
+                // closed
+                let _ = (&mut registration.waiter).await;
+                true
+            }
+
+            pub fn set(&self) {
+                let mut inner = self.inner.lock().unwrap();
+                if self.auto_reset {
+                    inner.release_one();
+                } else {
+                    inner.set = true;
+                    inner.waiters.wake_all(Release::Set);
+                }
+            }
+
+            pub fn pulse(&self) {
+                let mut inner = self.inner.lock().unwrap();
+                inner.set = false;
+                if self.auto_reset {
+                    inner.waiters.wake_one(Release::Pulse);
+                } else {
+                    inner.waiters.wake_all(Release::Pulse);
+                }
+            }
+
+            pub fn clear(&self) {
+                self.inner.lock().unwrap().set = false;
+            }
+
+            pub fn is_set(&self) -> bool {
+                self.inner.lock().unwrap().set
+            }
+        }
+
+        impl Inner {
+            fn release_one(&mut self) {
+                if !self.waiters.wake_one(Release::Set) {
+                    self.set = true;
+                }
+            }
+        }
+
+        struct Registration<'a> {
+            event: &'a Event,
+            key: u64,
+            waiter: Waiter<Release>,
+        }
+
+        impl Drop for Registration<'_> {
+            fn drop(&mut self) {
+                let mut inner = self.event.inner.lock().unwrap();
+                if !inner.waiters.remove(self.key)
+                    && self.event.auto_reset
+                    && self.waiter.try_take() == Some(Release::Set)
+                {
+                    // this waiter was released by a set but dropped before it could return, so
+                    // hand the release on to the next waiter instead of losing it
+                    inner.release_one();
+                }
+            }
+        }
+    }
+
+    mod waiter {
+        use std::{
+            future::{poll_fn, Future},
+            mem,
+            pin::Pin,
+            sync::{Arc, Mutex},
+            task::{Context, Poll, Waker},
+        };
+
+        #[derive(Debug)]
+        pub struct Closed;
+
+        #[derive(Debug)]
+        pub struct Waiter<T> {
+            slot: Slot<T>,
+            done: bool,
+        }
+
+        impl<T> Waiter<T> {
+            pub fn new() -> (WakeHandle<T>, Self) {
+                let (handle, slot) = Slot::new();
+                (handle, Self { slot, done: false })
+            }
+
+            pub fn try_take(&self) -> Option<T> {
+                self.slot.shared.lock().unwrap().value.take()
+            }
+        }
+
+        impl<T> Clone for Waiter<T> {
+            fn clone(&self) -> Self {
+                Self {
+                    slot: self.slot.clone(),
+                    done: self.done,
+                }
+            }
+        }
+
+        impl<T> Future for Waiter<T> {
+            type Output = Result<T, Closed>;
+
+            fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+                if self.done {
+                    return Poll::Pending;
+                }
+                let poll = self.slot.poll(cx, Option::take);
+                self.done = poll.is_ready();
+                poll
+            }
+        }
+
+        #[derive(Debug)]
+        pub struct WakeHandle<T> {
+            shared: Arc<Mutex<Shared<T>>>,
+        }
+
+        impl<T> WakeHandle<T> {
+            pub fn wake(&self, v: T) -> Result<(), T> {
+                let wakers = {
+                    let mut shared = self.shared.lock().unwrap();
+                    if shared.receivers == 0 {
+                        return Err(v);
+                    }
+                    shared.value = Some(v);
+                    shared.rx_wakers.take()
+                };
+                wakers.for_each(Waker::wake);
+                Ok(())
+            }
+
+            pub fn is_closed(&self) -> bool {
+                self.shared.lock().unwrap().receivers == 0
+            }
+
+            pub async fn closed(&self) {
+                let mut registration = Registration {
+                    shared: &self.shared,
+                    key: None,
+                };
+                poll_fn(|cx| {
+                    let mut shared = self.shared.lock().unwrap();
+                    if shared.receivers == 0 {
+                        Poll::Ready(())
+                    } else {
+                        shared.tx_wakers.register(&mut registration.key, cx.waker());
+                        Poll::Pending
+                    }
+                })
+                .await
+            }
+        }
+
+        impl<T> Clone for WakeHandle<T> {
+            fn clone(&self) -> Self {
+                self.shared.lock().unwrap().handles += 1;
+                Self {
+                    shared: self.shared.clone(),
+                }
+            }
+        }
+
+        impl<T> Drop for WakeHandle<T> {
+            fn drop(&mut self) {
+                let wakers = {
+                    let mut shared = self.shared.lock().unwrap();
+                    shared.handles -= 1;
+                    if shared.handles > 0 {
+                        return;
+                    }
+                    // the waiters can never be woken up now, so let them see that they're closed
+                    shared.rx_wakers.take()
+                };
+                wakers.for_each(Waker::wake);
+            }
+        }
+
+        #[derive(Debug)]
+        struct Shared<T> {
+            value: Option<T>,
+            handles: usize,
+            receivers: usize,
+            rx_wakers: Wakers,
+            tx_wakers: Wakers,
+        }
+
+        #[derive(Debug)]
+        struct Wakers {
+            next_key: usize,
+            entries: Vec<(usize, Waker)>,
+        }
+
+        impl Wakers {
+            fn new() -> Self {
+                Self {
+                    next_key: 0,
+                    entries: Vec::new(),
+                }
+            }
+
+            fn register(&mut self, key: &mut Option<usize>, waker: &Waker) {
+                match key.and_then(|key| self.entries.iter_mut().find(|(k, _)| *k == key)) {
+                    Some((_, entry)) => entry.clone_from(waker),
+                    None => {
+                        let k = self.next_key;
+                        self.next_key += 1;
+                        self.entries.push((k, waker.clone()));
+                        *key = Some(k);
+                    },
+                }
+            }
+
+            fn remove(&mut self, key: usize) {
+                self.entries.retain(|(k, _)| *k != key);
+            }
+
+            fn take(&mut self) -> impl Iterator<Item = Waker> {
+                mem::take(&mut self.entries)
+                    .into_iter()
+                    .map(|(_, waker)| waker)
+            }
+        }
+
+        struct Registration<'a, T> {
+            shared: &'a Mutex<Shared<T>>,
+            key: Option<usize>,
+        }
+
+        impl<T> Drop for Registration<'_, T> {
+            fn drop(&mut self) {
+                if let Some(key) = self.key {
+                    self.shared.lock().unwrap().tx_wakers.remove(key);
+                }
+            }
+        }
+
+        #[derive(Debug)]
+        struct Slot<T> {
+            shared: Arc<Mutex<Shared<T>>>,
+            key: Option<usize>,
+        }
+
+        impl<T> Slot<T> {
+            fn new() -> (WakeHandle<T>, Self) {
+                let shared = Arc::new(Mutex::new(Shared {
+                    value: None,
+                    handles: 1,
+                    receivers: 1,
+                    rx_wakers: Wakers::new(),
+                    tx_wakers: Wakers::new(),
+                }));
+                let handle = WakeHandle {
+                    shared: shared.clone(),
+                };
+                (handle, Self { shared, key: None })
+            }
+
+            fn poll(
+                &mut self,
+                cx: &mut Context<'_>,
+                get: impl FnOnce(&mut Option<T>) -> Option<T>,
+            ) -> Poll<Result<T, Closed>> {
+                let mut shared = self.shared.lock().unwrap();
+                let result = match get(&mut shared.value) {
+                    Some(v) => Ok(v),
+                    None if shared.handles == 0 => Err(Closed),
+                    None => {
+                        shared.rx_wakers.register(&mut self.key, cx.waker());
+                        return Poll::Pending;
+                    },
+                };
+                if let Some(key) = self.key.take() {
+                    shared.rx_wakers.remove(key);
+                }
+                Poll::Ready(result)
+            }
+        }
+
+        impl<T> Clone for Slot<T> {
+            fn clone(&self) -> Self {
+                self.shared.lock().unwrap().receivers += 1;
+                Self {
+                    shared: self.shared.clone(),
+                    key: None,
+                }
+            }
+        }
+
+        impl<T> Drop for Slot<T> {
+            fn drop(&mut self) {
+                let wakers = {
+                    let mut shared = self.shared.lock().unwrap();
+                    if let Some(key) = self.key {
+                        shared.rx_wakers.remove(key);
+                    }
+                    shared.receivers -= 1;
+                    if shared.receivers > 0 {
+                        return;
+                    }
+                    shared.tx_wakers.take()
+                };
+                wakers.for_each(Waker::wake);
+            }
+        }
+    }
+}
@@ -0,0 +1,110 @@
+//! A waker slot that can be registered and woken concurrently without a lock, following the
+//! `AtomicWaker` from `futures`
+
+use core::{
+    cell::UnsafeCell,
+    fmt,
+    sync::atomic::{AtomicUsize, Ordering},
+    task::Waker,
+};
+
+/// Nothing is touching the waker
+const WAITING: usize = 0;
+/// A task is registering a new waker, only it may touch the waker
+const REGISTERING: usize = 0b01;
+/// The waker is being taken to be woken, only the waking side may touch the waker
+const WAKING: usize = 0b10;
+
+pub(crate) struct AtomicWaker {
+    state: AtomicUsize,
+    waker: UnsafeCell<Option<Waker>>,
+}
+
+// SAFETY: the waker is only ever accessed by whoever moved the state out of `WAITING`, which makes
+// that access exclusive
+unsafe impl Send for AtomicWaker {}
+unsafe impl Sync for AtomicWaker {}
+
+impl AtomicWaker {
+    pub(crate) const fn new() -> Self {
+        Self {
+            state: AtomicUsize::new(WAITING),
+            waker: UnsafeCell::new(None),
+        }
+    }
+
+    /// Registers `waker` to be woken by the next call to `AtomicWaker::wake`. The condition being
+    /// waited on must be checked after registering, so that a wake in between isn't missed.
+    pub(crate) fn register(&self, waker: &Waker) {
+        match self
+            .state
+            .compare_exchange(WAITING, REGISTERING, Ordering::Acquire, Ordering::Acquire)
+            .unwrap_or_else(|state| state)
+        {
+            WAITING => {
+                // SAFETY: moving the state to `REGISTERING` gives exclusive access to the waker
+                unsafe {
+                    match &*self.waker.get() {
+                        Some(old) if old.will_wake(waker) => {},
+                        _ => *self.waker.get() = Some(waker.clone()),
+                    }
+                }
+                if let Err(state) = self.state.compare_exchange(
+                    REGISTERING,
+                    WAITING,
+                    Ordering::AcqRel,
+                    Ordering::Acquire,
+                ) {
+                    // a wake happened while registering and left the waker for us to wake
+                    debug_assert_eq!(state, REGISTERING | WAKING);
+                    // SAFETY: the waking side doesn't touch the waker while `REGISTERING` is set
+                    let waker = unsafe { (*self.waker.get()).take() };
+                    self.state.swap(WAITING, Ordering::AcqRel);
+                    if let Some(waker) = waker {
+                        waker.wake();
+                    }
+                }
+            },
+            WAKING => {
+                // the slot is being woken right now, so wake the new waker directly
+                waker.wake_by_ref();
+            },
+            state => {
+                // registering concurrently with ourselves, which can't happen through `&mut` polls
+                debug_assert!(state == REGISTERING || state == REGISTERING | WAKING);
+            },
+        }
+    }
+
+    /// Takes the registered waker out of the slot, if there is one
+    pub(crate) fn take(&self) -> Option<Waker> {
+        match self.state.fetch_or(WAKING, Ordering::AcqRel) {
+            WAITING => {
+                // SAFETY: moving the state to `WAKING` gives exclusive access to the waker
+                let waker = unsafe { (*self.waker.get()).take() };
+                self.state.fetch_and(!WAKING, Ordering::Release);
+                waker
+            },
+            state => {
+                // a registration is in progress and will wake its own waker when it sees `WAKING`,
+                // or another wake is already taking care of it
+                debug_assert!(
+                    state == REGISTERING || state == REGISTERING | WAKING || state == WAKING
+                );
+                None
+            },
+        }
+    }
+}
+
+impl Default for AtomicWaker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for AtomicWaker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("AtomicWaker")
+    }
+}
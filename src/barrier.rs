@@ -0,0 +1,128 @@
+use crate::{
+    list::{Cancelled, Registration, WaitList, WaitState, Wakeups},
+    lock::Lock,
+};
+
+/// The Barrier primitive makes a fixed number of futures wait for each other, none of them
+/// continue until all of them have reached the barrier.
+///
+/// # Example
+///
+/// ```rs
+/// use casus::Barrier;
+///
+/// let barrier = Barrier::new(4);
+///
+/// // this will block until 4 futures are waiting on the barrier
+/// if barrier.wait().await.is_leader() {
+///     println!("every task reached the barrier");
+/// }
+/// ```
+///
+/// Once every future has reached it, the barrier resets and can be waited on again for the next
+/// generation.
+
+#[derive(Debug)]
+pub struct Barrier {
+    n: usize,
+    state: Lock<State>,
+}
+
+#[derive(Debug)]
+struct State {
+    arrived: usize,
+    waiters: WaitList<()>,
+}
+
+/// What `Barrier::wait` returns, which tells exactly one of the futures in each generation that
+/// it's the leader
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BarrierWaitResult {
+    leader: bool,
+}
+
+impl BarrierWaitResult {
+    /// Checks if this future was the leader of its generation, which is the last one to reach the
+    /// barrier
+    ///
+    /// # Example
+    /// ```rs
+    /// if barrier.wait().await.is_leader() {
+    ///     println!("every task reached the barrier");
+    /// }
+    /// ```
+    pub fn is_leader(&self) -> bool {
+        self.leader
+    }
+}
+
+impl Barrier {
+    /// Creates a new `Barrier` that releases its waiters once `n` of them are waiting. A barrier
+    /// for no futures releases every future straight away, like one for a single future.
+    ///
+    /// # Example
+    /// ```rs
+    /// use casus::Barrier;
+    ///
+    /// let barrier = Barrier::new(4);
+    /// ```
+    pub fn new(n: usize) -> Self {
+        Self {
+            n,
+            state: Lock::new(State {
+                arrived: 0,
+                waiters: WaitList::new(),
+            }),
+        }
+    }
+
+    /// Waits for every future to reach the barrier. A future that is dropped while waiting no
+    /// longer counts towards the generation it was waiting on.
+    ///
+    /// # Example
+    /// ```rs
+    /// // will return when `n` futures are waiting on the barrier
+    /// barrier.wait().await;
+    /// ```
+    pub async fn wait(&self) -> BarrierWaitResult {
+        let mut registration = {
+            let mut wakeups = Wakeups::new();
+            let mut state = self.state.lock();
+            state.arrived += 1;
+            if state.arrived >= self.n {
+                state.arrived = 0;
+                state.waiters.wake_all((), &mut wakeups);
+                return BarrierWaitResult { leader: true };
+            }
+            Registration::new(&self.state, &mut state, ())
+        };
+        registration.wait().await;
+        BarrierWaitResult { leader: false }
+    }
+
+    /// Returns the number of futures the barrier waits for
+    ///
+    /// # Example
+    /// ```rs
+    /// assert_eq!(Barrier::new(4).n(), 4);
+    /// ```
+    pub fn n(&self) -> usize {
+        self.n
+    }
+}
+
+impl WaitState for State {
+    type Value = ();
+    type Data = ();
+
+    fn waiters(&mut self) -> &mut WaitList<()> {
+        &mut self.waiters
+    }
+
+    fn cancelled(&mut self, cancelled: Cancelled<(), ()>, _wakeups: &mut Wakeups<()>) {
+        // cancelled waits don't count towards the generation they were waiting on
+        if let Cancelled::Registered(()) = cancelled {
+            self.arrived -= 1;
+        }
+    }
+}
@@ -0,0 +1,60 @@
+//! Drives futures to completion on the calling thread by parking it until they're woken, so that
+//! sync code can wait on the same primitives as async code
+
+use std::{
+    future::Future,
+    pin::pin,
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
+    thread::{self, Thread},
+    time::Instant,
+};
+
+use crate::TimedOut;
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Blocks the current thread until `fut` completes
+pub(crate) fn block_on<F: Future>(fut: F) -> F::Output {
+    match block_on_deadline(fut, None) {
+        Ok(v) => v,
+        Err(TimedOut) => unreachable!("waits without a deadline can't time out"),
+    }
+}
+
+/// Blocks the current thread until `fut` completes or `deadline` passes, a deadline of `None`
+/// never passes
+pub(crate) fn block_on_deadline<F: Future>(
+    fut: F,
+    deadline: Option<Instant>,
+) -> Result<F::Output, TimedOut> {
+    let mut fut = pin!(fut);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        if let Poll::Ready(v) = fut.as_mut().poll(&mut cx) {
+            return Ok(v);
+        }
+        // parking can wake up spuriously, so the future is always polled again before giving up
+        match deadline {
+            Some(deadline) => {
+                let now = Instant::now();
+                if now >= deadline {
+                    return Err(TimedOut);
+                }
+                thread::park_timeout(deadline - now);
+            },
+            None => thread::park(),
+        }
+    }
+}
@@ -0,0 +1,194 @@
+use alloc::{
+    collections::BTreeMap,
+    sync::{Arc, Weak},
+    vec::Vec,
+};
+use core::{
+    future::{poll_fn, Future},
+    pin::pin,
+    task::Poll,
+};
+
+use crate::{lock::Lock, Event};
+
+/// The CancellationToken primitive signals cancellation to any number of futures, and passes it
+/// on to every child token created from it.
+///
+/// # Example
+///
+/// ```rs
+/// use casus::CancellationToken;
+///
+/// let token = CancellationToken::new();
+/// let child = token.child_token();
+///
+/// tokio::spawn(async move {
+///     // this will block until the token or one of its ancestors is cancelled
+///     child.cancelled().await;
+/// });
+///
+/// token.cancel();
+/// ```
+///
+/// Clones of a token share its cancellation, while cancelling a child leaves its parent alone.
+/// A child is removed from its parent once every clone of it has been dropped.
+
+#[derive(Clone, Debug)]
+pub struct CancellationToken {
+    node: Arc<Node>,
+}
+
+#[derive(Debug)]
+struct Node {
+    // set once the token is cancelled, and never closed
+    event: Event,
+    children: Lock<Children>,
+    // the parent and the key this node is stored under in its children
+    parent: Option<(Arc<Node>, u64)>,
+}
+
+#[derive(Debug)]
+struct Children {
+    next_key: u64,
+    nodes: BTreeMap<u64, Weak<Node>>,
+}
+
+impl Node {
+    fn new(parent: Option<(Arc<Node>, u64)>) -> Self {
+        Self {
+            event: Event::new(),
+            children: Lock::new(Children {
+                next_key: 0,
+                nodes: BTreeMap::new(),
+            }),
+            parent,
+        }
+    }
+}
+
+impl Drop for Node {
+    fn drop(&mut self) {
+        if let Some((parent, key)) = &self.parent {
+            parent.children.lock().nodes.remove(key);
+        }
+    }
+}
+
+impl CancellationToken {
+    /// Creates a new `CancellationToken` that hasn't been cancelled
+    ///
+    /// # Example
+    /// ```rs
+    /// use casus::CancellationToken;
+    ///
+    /// let token = CancellationToken::new();
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            node: Arc::new(Node::new(None)),
+        }
+    }
+
+    /// Creates a child token, which is cancelled along with this token but can also be cancelled
+    /// on its own. A child of a token that is already cancelled starts out cancelled.
+    ///
+    /// # Example
+    /// ```rs
+    /// let child = token.child_token();
+    /// token.cancel();
+    /// assert!(child.is_cancelled());
+    /// ```
+    pub fn child_token(&self) -> Self {
+        let mut children = self.node.children.lock();
+        let key = children.next_key;
+        children.next_key += 1;
+        let child = Arc::new(Node::new(Some((self.node.clone(), key))));
+        // checked while holding the lock, so either this sees the cancellation or the
+        // cancellation sees the child
+        if self.is_cancelled() {
+            let _ = child.event.set();
+        } else {
+            children.nodes.insert(key, Arc::downgrade(&child));
+        }
+        Self { node: child }
+    }
+
+    /// Cancels the token along with all of its descendants, waking every future waiting on them.
+    /// Cancelling a token that is already cancelled does nothing.
+    ///
+    /// # Example
+    /// ```rs
+    /// token.cancel();
+    /// ```
+    pub fn cancel(&self) {
+        let mut pending = Vec::from([self.node.clone()]);
+        while let Some(node) = pending.pop() {
+            let _ = node.event.set();
+            let children = core::mem::take(&mut node.children.lock().nodes);
+            pending.extend(children.values().filter_map(Weak::upgrade));
+        }
+    }
+
+    /// Checks if the token has been cancelled, either directly or through one of its ancestors
+    ///
+    /// # Example
+    /// ```rs
+    /// if token.is_cancelled() {
+    ///     return;
+    /// }
+    /// ```
+    pub fn is_cancelled(&self) -> bool {
+        self.node.event.is_set()
+    }
+
+    /// Waits for the token to be cancelled
+    ///
+    /// # Example
+    /// ```rs
+    /// // will return when the token is cancelled
+    /// token.cancelled().await;
+    /// ```
+    pub async fn cancelled(&self) {
+        // the event is never closed, so waiting on it only ends once it's set
+        let _ = self.node.event.wait().await;
+    }
+
+    /// Waits for the token to be cancelled like `CancellationToken::cancelled`, taking the token by
+    /// value so that the future is `'static`
+    ///
+    /// # Example
+    /// ```rs
+    /// tokio::spawn(token.clone().cancelled_owned());
+    /// ```
+    pub async fn cancelled_owned(self) {
+        self.cancelled().await
+    }
+
+    /// Runs `fut` until it completes or the token is cancelled, returning `None` if it was
+    /// cancelled first. Once the token is cancelled `fut` isn't polled again.
+    ///
+    /// # Example
+    /// ```rs
+    /// match token.run_until_cancelled(fetch()).await {
+    ///     Some(response) => println!("fetched {response}"),
+    ///     None => println!("cancelled"),
+    /// }
+    /// ```
+    pub async fn run_until_cancelled<F: Future>(&self, fut: F) -> Option<F::Output> {
+        let mut fut = pin!(fut);
+        let mut cancelled = pin!(self.cancelled());
+        poll_fn(|cx| {
+            if cancelled.as_mut().poll(cx).is_ready() {
+                return Poll::Ready(None);
+            }
+            fut.as_mut().poll(cx).map(Some)
+        })
+        .await
+    }
+}
+
+impl Default for CancellationToken {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,298 @@
+//! A multi-producer, multi-consumer channel where every receiver gets a clone of every value
+
+use alloc::{collections::VecDeque, sync::Arc};
+use core::{
+    fmt,
+    future::{poll_fn, Future},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use super::SendError;
+use crate::{
+    list::{WaitList, Wakeups},
+    lock::Lock,
+    Waiter,
+};
+
+/// Creates a broadcast channel that keeps the last `capacity` values for receivers that are
+/// behind, returning the `Sender` used to send values and the first `Receiver`. More receivers
+/// are created with `Sender::subscribe`.
+///
+/// # Panics
+/// Panics if `capacity` is 0
+///
+/// # Example
+/// ```rs
+/// use casus::channel::broadcast;
+///
+/// let (sender, mut first) = broadcast::channel(16);
+/// let mut second = sender.subscribe();
+///
+/// sender.send(5)?;
+/// assert_eq!(first.recv().await, Ok(5));
+/// assert_eq!(second.recv().await, Ok(5));
+/// ```
+pub fn channel<T: Clone>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    assert!(
+        capacity > 0,
+        "broadcast channels need a capacity of at least 1"
+    );
+    let shared = Arc::new(Lock::new(State {
+        buffer: VecDeque::with_capacity(capacity),
+        capacity,
+        next: 0,
+        senders: 1,
+        receivers: 1,
+        waiters: WaitList::new(),
+    }));
+    let receiver = Receiver {
+        shared: shared.clone(),
+        next: 0,
+        registration: None,
+    };
+    (Sender { shared }, receiver)
+}
+
+#[derive(Debug)]
+struct State<T> {
+    // the last `capacity` values sent, the last of which has the sequence number `next - 1`
+    buffer: VecDeque<T>,
+    capacity: usize,
+    next: u64,
+    senders: usize,
+    receivers: usize,
+    waiters: WaitList<()>,
+}
+
+impl<T: Clone> State<T> {
+    /// Receives the value with the sequence number `next` for a receiver, moving it on to the next
+    /// value or, if it has fallen behind, to the oldest value still kept
+    fn recv(&self, next: &mut u64) -> Result<T, TryRecvError> {
+        let oldest = self.next - self.buffer.len() as u64;
+        if *next < oldest {
+            let missed = oldest - *next;
+            *next = oldest;
+            return Err(TryRecvError::Lagged(missed));
+        }
+        match self.buffer.get((*next - oldest) as usize) {
+            Some(v) => {
+                *next += 1;
+                Ok(v.clone())
+            },
+            None if self.senders == 0 => Err(TryRecvError::Closed),
+            None => Err(TryRecvError::Empty),
+        }
+    }
+}
+
+/// The sending side of a broadcast channel, which can be cloned to send from several places
+#[derive(Debug)]
+pub struct Sender<T> {
+    shared: Arc<Lock<State<T>>>,
+}
+
+impl<T: Clone> Sender<T> {
+    /// Sends `v` to every receiver, returning how many receivers there are, or handing it back as a
+    /// `SendError` if there are none. If the channel is full, the oldest value is dropped and
+    /// receivers that hadn't received it yet are told they lagged.
+    ///
+    /// # Example
+    /// ```rs
+    /// let receivers = sender.send(5)?;
+    /// ```
+    pub fn send(&self, v: T) -> Result<usize, SendError<T>> {
+        let mut wakeups = Wakeups::new();
+        let mut state = self.shared.lock();
+        if state.receivers == 0 {
+            return Err(SendError(v));
+        }
+        if state.buffer.len() == state.capacity {
+            state.buffer.pop_front();
+        }
+        state.buffer.push_back(v);
+        state.next += 1;
+        state.waiters.wake_all((), &mut wakeups);
+        Ok(state.receivers)
+    }
+
+    /// Creates a new `Receiver` that receives every value sent from now on
+    ///
+    /// # Example
+    /// ```rs
+    /// let mut receiver = sender.subscribe();
+    /// ```
+    pub fn subscribe(&self) -> Receiver<T> {
+        let mut state = self.shared.lock();
+        state.receivers += 1;
+        Receiver {
+            shared: self.shared.clone(),
+            next: state.next,
+            registration: None,
+        }
+    }
+
+    /// Returns the number of receivers
+    ///
+    /// # Example
+    /// ```rs
+    /// println!("sending to {} receivers", sender.receiver_count());
+    /// ```
+    pub fn receiver_count(&self) -> usize {
+        self.shared.lock().receivers
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        self.shared.lock().senders += 1;
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let mut wakeups = Wakeups::new();
+        let mut state = self.shared.lock();
+        state.senders -= 1;
+        if state.senders == 0 {
+            // let the receivers see that nothing more will be sent
+            state.waiters.wake_all((), &mut wakeups);
+        }
+    }
+}
+
+/// The receiving side of a broadcast channel
+#[derive(Debug)]
+pub struct Receiver<T> {
+    shared: Arc<Lock<State<T>>>,
+    // the sequence number of the next value to receive
+    next: u64,
+    registration: Option<(u64, Waiter<()>)>,
+}
+
+impl<T: Clone> Receiver<T> {
+    /// Waits for the next value, failing with `RecvError::Lagged` if values were dropped before
+    /// they could be received, or `RecvError::Closed` once every sender has been dropped and every
+    /// value sent before that has been received
+    ///
+    /// # Example
+    /// ```rs
+    /// loop {
+    ///     match receiver.recv().await {
+    ///         Ok(v) => println!("received {v}"),
+    ///         Err(RecvError::Lagged(missed)) => println!("missed {missed} values"),
+    ///         Err(RecvError::Closed) => break,
+    ///     }
+    /// }
+    /// ```
+    pub async fn recv(&mut self) -> Result<T, RecvError> {
+        poll_fn(|cx| self.poll_recv(cx)).await
+    }
+
+    /// Polls for the next value like `Receiver::recv`, for use in hand-written futures and streams
+    ///
+    /// # Example
+    /// ```rs
+    /// impl Stream for Values {
+    ///     type Item = Result<u32, RecvError>;
+    ///
+    ///     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+    ///         match self.receiver.poll_recv(cx) {
+    ///             Poll::Ready(Err(RecvError::Closed)) => Poll::Ready(None),
+    ///             poll => poll.map(Some),
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Result<T, RecvError>> {
+        loop {
+            if let Some((_, waiter)) = &mut self.registration {
+                if Pin::new(waiter).poll(cx).is_pending() {
+                    return Poll::Pending;
+                }
+                // waking the waiter already took it off the list
+                self.registration = None;
+            }
+            let mut state = self.shared.lock();
+            match state.recv(&mut self.next) {
+                Ok(v) => return Poll::Ready(Ok(v)),
+                Err(TryRecvError::Lagged(missed)) => {
+                    return Poll::Ready(Err(RecvError::Lagged(missed)))
+                },
+                Err(TryRecvError::Closed) => return Poll::Ready(Err(RecvError::Closed)),
+                Err(TryRecvError::Empty) => self.registration = Some(state.waiters.register()),
+            }
+        }
+    }
+
+    /// Receives the next value without waiting, failing with `TryRecvError::Empty` if there isn't
+    /// one yet, and otherwise the same way as `Receiver::recv`
+    ///
+    /// # Example
+    /// ```rs
+    /// while let Ok(v) = receiver.try_recv() {
+    ///     println!("received {v}");
+    /// }
+    /// ```
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        self.shared.lock().recv(&mut self.next)
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        let mut state = self.shared.lock();
+        state.receivers -= 1;
+        if let Some((key, _)) = &self.registration {
+            state.waiters.remove(*key);
+        }
+    }
+}
+
+/// The error returned when receiving from a broadcast channel fails
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RecvError {
+    /// Every sender has been dropped and nothing is left to receive
+    Closed,
+    /// The receiver fell behind and this many values were dropped before it could receive them,
+    /// the next receive continues from the oldest value still kept
+    Lagged(u64),
+}
+
+impl fmt::Display for RecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Closed => f.write_str("channel closed"),
+            Self::Lagged(missed) => write!(f, "receiver lagged behind by {} values", missed),
+        }
+    }
+}
+
+impl core::error::Error for RecvError {}
+
+/// The error returned when a value can't be received from a broadcast channel without waiting
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TryRecvError {
+    /// Nothing new has been sent yet
+    Empty,
+    /// Every sender has been dropped and nothing is left to receive
+    Closed,
+    /// The receiver fell behind and this many values were dropped before it could receive them,
+    /// the next receive continues from the oldest value still kept
+    Lagged(u64),
+}
+
+impl fmt::Display for TryRecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => f.write_str("channel empty"),
+            Self::Closed => f.write_str("channel closed"),
+            Self::Lagged(missed) => write!(f, "receiver lagged behind by {} values", missed),
+        }
+    }
+}
+
+impl core::error::Error for TryRecvError {}
@@ -0,0 +1,71 @@
+use core::fmt;
+
+/// The error returned when sending on a channel whose receiving side is gone, handing back the
+/// value that couldn't be sent
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SendError<T>(pub T);
+
+impl<T> fmt::Display for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("channel closed")
+    }
+}
+
+impl<T: fmt::Debug> core::error::Error for SendError<T> {}
+
+/// The error returned when a value can't be sent on a channel without waiting, handing back the
+/// value that couldn't be sent
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrySendError<T> {
+    /// The channel is full
+    Full(T),
+    /// The receiving side of the channel is gone
+    Closed(T),
+}
+
+impl<T> TrySendError<T> {
+    /// Returns the value that couldn't be sent
+    ///
+    /// # Example
+    /// ```rs
+    /// if let Err(err) = sender.try_send(value) {
+    ///     let value = err.into_inner();
+    /// }
+    /// ```
+    pub fn into_inner(self) -> T {
+        match self {
+            Self::Full(v) | Self::Closed(v) => v,
+        }
+    }
+}
+
+impl<T> fmt::Display for TrySendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Full(_) => f.write_str("channel full"),
+            Self::Closed(_) => f.write_str("channel closed"),
+        }
+    }
+}
+
+impl<T: fmt::Debug> core::error::Error for TrySendError<T> {}
+
+/// The error returned when a value can't be received from a channel without waiting
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TryRecvError {
+    /// Nothing has been sent yet
+    Empty,
+    /// The sending side of the channel is gone and nothing is left to receive
+    Closed,
+}
+
+impl fmt::Display for TryRecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => f.write_str("channel empty"),
+            Self::Closed => f.write_str("channel closed"),
+        }
+    }
+}
+
+impl core::error::Error for TryRecvError {}
@@ -0,0 +1,14 @@
+//! Channels for sending values between futures, none of which depend on a particular runtime.
+//!
+//! - `oneshot` sends a single value from one sender to one receiver
+//! - `mpsc` queues values from many senders for one receiver, either bounded, where sends wait for
+//!   space, or unbounded
+//! - `broadcast` gives every receiver a clone of every value, reporting how many values a receiver
+//!   missed if it falls too far behind
+
+pub mod broadcast;
+mod error;
+pub mod mpsc;
+pub mod oneshot;
+
+pub use error::{SendError, TryRecvError, TrySendError};
@@ -0,0 +1,500 @@
+//! A multi-producer, single-consumer queue, either bounded or unbounded
+
+use alloc::{collections::VecDeque, sync::Arc};
+use core::{
+    future::poll_fn,
+    task::{Context, Poll},
+};
+
+use super::{SendError, TryRecvError, TrySendError};
+use crate::{
+    atomic_waker::AtomicWaker, lock::Lock, semaphore::PendingAcquire, Closed, Semaphore,
+    SemaphorePermit, TryAcquireError,
+};
+
+/// Creates a bounded channel that holds up to `capacity` values, returning the `Sender` used to
+/// send values and the `Receiver` that receives them. Sending waits while the channel is full.
+///
+/// # Panics
+/// Panics if `capacity` is 0
+///
+/// # Example
+/// ```rs
+/// use casus::channel::mpsc;
+///
+/// let (sender, mut receiver) = mpsc::channel(16);
+///
+/// sender.send(5).await?;
+/// assert_eq!(receiver.recv().await, Some(5));
+/// ```
+pub fn channel<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    assert!(
+        capacity > 0,
+        "bounded channels need a capacity of at least 1"
+    );
+    let chan = Chan::new(Some(Semaphore::new(capacity)));
+    (Sender::new(chan.clone()), Receiver { chan })
+}
+
+/// Creates an unbounded channel, returning the `UnboundedSender` used to send values and the
+/// `Receiver` that receives them. Sending never waits, so nothing stops the queue from growing if
+/// the receiver falls behind.
+///
+/// # Example
+/// ```rs
+/// use casus::channel::mpsc;
+///
+/// let (sender, mut receiver) = mpsc::unbounded_channel();
+///
+/// sender.send(5)?;
+/// assert_eq!(receiver.recv().await, Some(5));
+/// ```
+pub fn unbounded_channel<T>() -> (UnboundedSender<T>, Receiver<T>) {
+    let chan = Chan::new(None);
+    (UnboundedSender { chan: chan.clone() }, Receiver { chan })
+}
+
+#[derive(Debug)]
+struct Chan<T> {
+    state: Lock<State<T>>,
+    // woken when a value is sent or the last sender is dropped
+    receiver: AtomicWaker,
+    // the free slots of a bounded channel, which is closed along with the channel so that waiting
+    // sends fail
+    slots: Option<Semaphore>,
+}
+
+#[derive(Debug)]
+struct State<T> {
+    queue: VecDeque<T>,
+    senders: usize,
+    closed: bool,
+}
+
+impl<T> Chan<T> {
+    fn new(slots: Option<Semaphore>) -> Arc<Self> {
+        Arc::new(Self {
+            state: Lock::new(State {
+                queue: VecDeque::new(),
+                senders: 1,
+                closed: false,
+            }),
+            receiver: AtomicWaker::new(),
+            slots,
+        })
+    }
+
+    /// Queues `v` for the receiver, handing it back if the channel has been closed
+    fn push(&self, v: T) -> Result<(), T> {
+        {
+            let mut state = self.state.lock();
+            if state.closed {
+                return Err(v);
+            }
+            state.queue.push_back(v);
+        }
+        self.wake_receiver();
+        Ok(())
+    }
+
+    fn wake_receiver(&self) {
+        if let Some(waker) = self.receiver.take() {
+            waker.wake();
+        }
+    }
+
+    fn close(&self) {
+        self.state.lock().closed = true;
+        if let Some(slots) = &self.slots {
+            slots.close();
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        self.state.lock().closed
+    }
+
+    fn add_sender(self: &Arc<Self>) -> Arc<Self> {
+        self.state.lock().senders += 1;
+        self.clone()
+    }
+
+    fn drop_sender(&self) {
+        let mut state = self.state.lock();
+        state.senders -= 1;
+        if state.senders == 0 {
+            drop(state);
+            // let the receiver see that nothing more will be sent
+            self.wake_receiver();
+        }
+    }
+}
+
+/// The sending side of a bounded channel, which can be cloned to send from several places
+#[derive(Debug)]
+pub struct Sender<T> {
+    chan: Arc<Chan<T>>,
+    // the space reserved by `Sender::poll_reserve` for the next `Sender::send_item`
+    reservation: Reservation,
+}
+
+#[derive(Debug)]
+enum Reservation {
+    None,
+    Pending(PendingAcquire),
+    Reserved,
+}
+
+impl<T> Sender<T> {
+    fn new(chan: Arc<Chan<T>>) -> Self {
+        Self {
+            chan,
+            reservation: Reservation::None,
+        }
+    }
+
+    /// Waits for space in the channel and sends `v`, handing it back as a `SendError` if the
+    /// receiver is closed or dropped first
+    ///
+    /// # Example
+    /// ```rs
+    /// if let Err(SendError(v)) = sender.send(5).await {
+    ///     // nothing is left to receive v
+    /// }
+    /// ```
+    pub async fn send(&self, v: T) -> Result<(), SendError<T>> {
+        match self.reserve().await {
+            Ok(permit) => permit.send(v),
+            Err(Closed) => Err(SendError(v)),
+        }
+    }
+
+    /// Sends `v` without waiting, handing it back in a `TrySendError::Full` if the channel is full
+    /// or a `TrySendError::Closed` if the receiver is gone
+    ///
+    /// # Example
+    /// ```rs
+    /// match sender.try_send(5) {
+    ///     Ok(()) => println!("sent"),
+    ///     Err(TrySendError::Full(v)) => println!("no space for {v}"),
+    ///     Err(TrySendError::Closed(v)) => println!("nothing is left to receive {v}"),
+    /// }
+    /// ```
+    pub fn try_send(&self, v: T) -> Result<(), TrySendError<T>> {
+        match self.slots().try_acquire(1) {
+            Ok(permit) => Permit {
+                chan: &self.chan,
+                permit,
+            }
+            .send(v)
+            .map_err(|SendError(v)| TrySendError::Closed(v)),
+            Err(TryAcquireError::NoPermits) => Err(TrySendError::Full(v)),
+            Err(TryAcquireError::Closed) => Err(TrySendError::Closed(v)),
+        }
+    }
+
+    /// Waits for space in the channel and reserves it, so a value can be sent later without
+    /// waiting. Reservations are served in the order they started waiting, and the space is given
+    /// back if the `Permit` is dropped without sending.
+    ///
+    /// # Example
+    /// ```rs
+    /// let permit = sender.reserve().await?;
+    /// // sending through the permit can't wait for space
+    /// permit.send(compute_value())?;
+    /// ```
+    pub async fn reserve(&self) -> Result<Permit<'_, T>, Closed> {
+        let permit = self.slots().acquire(1).await?;
+        Ok(Permit {
+            chan: &self.chan,
+            permit,
+        })
+    }
+
+    /// Polls for space in the channel and reserves it for the next `Sender::send_item`, for use in
+    /// hand-written futures and sinks. Like `Sender::reserve`, the wait keeps its place in line
+    /// between polls, and returns immediately if space is already reserved.
+    ///
+    /// # Example
+    /// ```rs
+    /// impl Future for SendValue {
+    ///     type Output = Result<(), SendError<u32>>;
+    ///
+    ///     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+    ///         if let Err(Closed) = ready!(self.sender.poll_reserve(cx)) {
+    ///             return Poll::Ready(Err(SendError(self.value)));
+    ///         }
+    ///         Poll::Ready(self.sender.send_item(self.value))
+    ///     }
+    /// }
+    /// ```
+    pub fn poll_reserve(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Closed>> {
+        let mut pending = match core::mem::replace(&mut self.reservation, Reservation::None) {
+            Reservation::None => None,
+            Reservation::Pending(pending) => Some(pending),
+            Reservation::Reserved => {
+                self.reservation = Reservation::Reserved;
+                return Poll::Ready(Ok(()));
+            },
+        };
+        let result = self.slots().poll_acquire_permits(1, &mut pending, cx);
+        self.reservation = match (&result, pending) {
+            (Poll::Ready(Ok(())), _) => Reservation::Reserved,
+            (_, Some(pending)) => Reservation::Pending(pending),
+            (_, None) => Reservation::None,
+        };
+        result
+    }
+
+    /// Sends `v` into the space reserved by `Sender::poll_reserve`, handing it back as a
+    /// `SendError` if the receiver has been closed or dropped since the space was reserved
+    ///
+    /// # Panics
+    /// Panics if no space has been reserved
+    ///
+    /// # Example
+    /// ```rs
+    /// if let Poll::Ready(Ok(())) = sender.poll_reserve(cx) {
+    ///     sender.send_item(5)?;
+    /// }
+    /// ```
+    pub fn send_item(&mut self, v: T) -> Result<(), SendError<T>> {
+        assert!(
+            matches!(self.reservation, Reservation::Reserved),
+            "send_item was called without reserving space with poll_reserve"
+        );
+        self.reservation = Reservation::None;
+        let result = self.chan.push(v).map_err(SendError);
+        if result.is_err() {
+            self.slots().add_permits(1);
+        }
+        result
+    }
+
+    /// Gives back the space reserved by `Sender::poll_reserve`, or stops waiting for it, returning
+    /// whether there was anything to give up
+    ///
+    /// # Example
+    /// ```rs
+    /// // the value to send is no longer needed
+    /// sender.abort_reserve();
+    /// ```
+    pub fn abort_reserve(&mut self) -> bool {
+        match core::mem::replace(&mut self.reservation, Reservation::None) {
+            Reservation::None => false,
+            Reservation::Pending(pending) => {
+                self.slots().cancel_acquire(pending);
+                true
+            },
+            Reservation::Reserved => {
+                self.slots().add_permits(1);
+                true
+            },
+        }
+    }
+
+    /// Returns the number of values that can be sent before the channel is full
+    ///
+    /// # Example
+    /// ```rs
+    /// println!("space for {} more values", sender.capacity());
+    /// ```
+    pub fn capacity(&self) -> usize {
+        self.slots().available_permits()
+    }
+
+    /// Checks if the receiver has been closed or dropped, meaning sending would fail
+    ///
+    /// # Example
+    /// ```rs
+    /// if sender.is_closed() {
+    ///     return;
+    /// }
+    /// ```
+    pub fn is_closed(&self) -> bool {
+        self.chan.is_closed()
+    }
+
+    fn slots(&self) -> &Semaphore {
+        self.chan
+            .slots
+            .as_ref()
+            .expect("bounded channels are created with slots")
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        // reservations belong to the sender that made them
+        Self::new(self.chan.add_sender())
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        self.abort_reserve();
+        self.chan.drop_sender();
+    }
+}
+
+/// Space reserved in a bounded channel by `Sender::reserve`, which is given back if the permit is
+/// dropped without sending
+#[derive(Debug)]
+#[must_use = "the space is given back as soon as the permit is dropped"]
+pub struct Permit<'a, T> {
+    chan: &'a Chan<T>,
+    permit: SemaphorePermit<'a>,
+}
+
+impl<T> Permit<'_, T> {
+    /// Sends `v` into the reserved space, handing it back as a `SendError` if the receiver has been
+    /// closed or dropped since the space was reserved
+    ///
+    /// # Example
+    /// ```rs
+    /// sender.reserve().await?.send(5)?;
+    /// ```
+    pub fn send(self, v: T) -> Result<(), SendError<T>> {
+        self.chan.push(v).map_err(SendError)?;
+        // the receiver gives the space back once it receives the value
+        self.permit.forget();
+        Ok(())
+    }
+}
+
+/// The sending side of an unbounded channel, which can be cloned to send from several places
+#[derive(Debug)]
+pub struct UnboundedSender<T> {
+    chan: Arc<Chan<T>>,
+}
+
+impl<T> UnboundedSender<T> {
+    /// Sends `v` without waiting, handing it back as a `SendError` if the receiver has been closed
+    /// or dropped
+    ///
+    /// # Example
+    /// ```rs
+    /// if let Err(SendError(v)) = sender.send(5) {
+    ///     // nothing is left to receive v
+    /// }
+    /// ```
+    pub fn send(&self, v: T) -> Result<(), SendError<T>> {
+        self.chan.push(v).map_err(SendError)
+    }
+
+    /// Checks if the receiver has been closed or dropped, meaning sending would fail
+    ///
+    /// # Example
+    /// ```rs
+    /// if sender.is_closed() {
+    ///     return;
+    /// }
+    /// ```
+    pub fn is_closed(&self) -> bool {
+        self.chan.is_closed()
+    }
+}
+
+impl<T> Clone for UnboundedSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            chan: self.chan.add_sender(),
+        }
+    }
+}
+
+impl<T> Drop for UnboundedSender<T> {
+    fn drop(&mut self) {
+        self.chan.drop_sender();
+    }
+}
+
+/// The receiving side of a bounded or unbounded channel
+#[derive(Debug)]
+pub struct Receiver<T> {
+    chan: Arc<Chan<T>>,
+}
+
+impl<T> Receiver<T> {
+    /// Waits for the next value, returning `None` once every sender has been dropped, or the
+    /// channel has been closed, and every value sent before that has been received
+    ///
+    /// # Example
+    /// ```rs
+    /// while let Some(v) = receiver.recv().await {
+    ///     println!("received {v}");
+    /// }
+    /// ```
+    pub async fn recv(&mut self) -> Option<T> {
+        poll_fn(|cx| self.poll_recv(cx)).await
+    }
+
+    /// Polls for the next value like `Receiver::recv`, for use in hand-written futures and streams
+    ///
+    /// # Example
+    /// ```rs
+    /// impl Stream for Values {
+    ///     type Item = u32;
+    ///
+    ///     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<u32>> {
+    ///         self.receiver.poll_recv(cx)
+    ///     }
+    /// }
+    /// ```
+    pub fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        // registering before checking the queue means a send in between can't be missed
+        self.chan.receiver.register(cx.waker());
+        match self.try_recv() {
+            Ok(v) => Poll::Ready(Some(v)),
+            Err(TryRecvError::Closed) => Poll::Ready(None),
+            Err(TryRecvError::Empty) => Poll::Pending,
+        }
+    }
+
+    /// Receives the next value without waiting, failing with `TryRecvError::Empty` if there isn't
+    /// one yet or `TryRecvError::Closed` if there never will be
+    ///
+    /// # Example
+    /// ```rs
+    /// while let Ok(v) = receiver.try_recv() {
+    ///     println!("received {v}");
+    /// }
+    /// ```
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        let mut state = self.chan.state.lock();
+        match state.queue.pop_front() {
+            Some(v) => {
+                drop(state);
+                if let Some(slots) = &self.chan.slots {
+                    slots.add_permits(1);
+                }
+                Ok(v)
+            },
+            None if state.closed || state.senders == 0 => Err(TryRecvError::Closed),
+            None => Err(TryRecvError::Empty),
+        }
+    }
+
+    /// Closes the channel, failing every current and future send, while still letting the values
+    /// that were already sent be received
+    ///
+    /// # Example
+    /// ```rs
+    /// receiver.close();
+    /// while let Some(v) = receiver.recv().await {
+    ///     println!("received {v}");
+    /// }
+    /// ```
+    pub fn close(&mut self) {
+        self.chan.close();
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.chan.close();
+        // drop the values nobody will receive now rather than when the last sender goes
+        let queue = core::mem::take(&mut self.chan.state.lock().queue);
+        drop(queue);
+    }
+}
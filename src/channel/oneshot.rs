@@ -0,0 +1,112 @@
+//! A channel for sending a single value, built directly on `Waiter`
+
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use super::TryRecvError;
+use crate::{Closed, Waiter, WakeHandle};
+
+/// Creates a oneshot channel, returning the `Sender` that sends its value and the `Receiver` that
+/// receives it
+///
+/// # Example
+/// ```rs
+/// use casus::channel::oneshot;
+///
+/// let (sender, receiver) = oneshot::channel();
+///
+/// sender.send(5).unwrap();
+/// assert_eq!(receiver.await, Ok(5));
+/// ```
+pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
+    let (handle, waiter) = Waiter::new();
+    (Sender { handle }, Receiver { waiter })
+}
+
+/// The sending side of a oneshot channel. Dropping it without sending closes the channel, so the
+/// receiver fails with `Closed` instead of waiting forever.
+#[derive(Debug)]
+pub struct Sender<T> {
+    handle: WakeHandle<T>,
+}
+
+impl<T> Sender<T> {
+    /// Sends `v` to the receiver, handing it back as `Err(v)` if the receiver has been dropped
+    ///
+    /// # Example
+    /// ```rs
+    /// if let Err(v) = sender.send(5) {
+    ///     // nothing is left to receive v
+    /// }
+    /// ```
+    pub fn send(self, v: T) -> Result<(), T> {
+        self.handle.wake(v)
+    }
+
+    /// Checks if the receiver has been dropped, meaning sending would fail
+    ///
+    /// # Example
+    /// ```rs
+    /// if sender.is_closed() {
+    ///     return;
+    /// }
+    /// ```
+    pub fn is_closed(&self) -> bool {
+        self.handle.is_closed()
+    }
+
+    /// Waits for the receiver to be dropped, which allows work whose result nobody is waiting for
+    /// anymore to be cancelled early
+    ///
+    /// # Example
+    /// ```rs
+    /// // will return when the receiver has been dropped
+    /// sender.closed().await;
+    /// ```
+    pub async fn closed(&self) {
+        self.handle.closed().await
+    }
+}
+
+/// The receiving side of a oneshot channel, which is a future that completes with the value sent,
+/// or with `Closed` if the sender is dropped without sending
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Receiver<T> {
+    waiter: Waiter<T>,
+}
+
+impl<T> Receiver<T> {
+    /// Receives the value without waiting, failing with `TryRecvError::Empty` if it hasn't been
+    /// sent yet or `TryRecvError::Closed` if the sender was dropped without sending
+    ///
+    /// # Example
+    /// ```rs
+    /// match receiver.try_recv() {
+    ///     Ok(v) => println!("received {v}"),
+    ///     Err(TryRecvError::Empty) => println!("nothing yet"),
+    ///     Err(TryRecvError::Closed) => println!("the sender is gone"),
+    /// }
+    /// ```
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        if let Some(v) = self.waiter.try_take() {
+            return Ok(v);
+        }
+        if !self.waiter.is_closed() {
+            return Err(TryRecvError::Empty);
+        }
+        // the sender may have sent its value just before being dropped
+        self.waiter.try_take().ok_or(TryRecvError::Closed)
+    }
+}
+
+impl<T> Future for Receiver<T> {
+    type Output = Result<T, Closed>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.waiter).poll(cx)
+    }
+}
@@ -0,0 +1,179 @@
+use alloc::{boxed::Box, sync::Arc, vec::Vec};
+use core::fmt;
+
+use crate::{
+    event::{self, sealed::Sealed, EventSource, SourceWait},
+    AutoResetSource, EventClosed, WaitResult,
+};
+
+/// The CompositeEvent primitive is a view over other events that is set while all of them, or
+/// any of them, are set. It holds no state of its own, so it follows its sources as they're set
+/// and cleared.
+///
+/// # Example
+///
+/// ```rs
+/// use casus::CompositeEvent;
+///
+/// let db_ready = Arc::new(Event::new());
+/// let cache_ready = Arc::new(Event::new());
+/// let ready = CompositeEvent::all([db_ready.clone(), cache_ready.clone()])?;
+///
+/// // this will block until both sources are set
+/// ready.wait().await?;
+/// ```
+///
+/// A composite is an `EventSource` itself, so composites can be made of other composites and
+/// waited on along with events by `Event::wait_any` and `Event::wait_all`. Only manual-reset
+/// events can be sources, since waiting on an auto-reset event would take its release away from
+/// its own waiters.
+
+#[derive(Clone)]
+pub struct CompositeEvent {
+    sources: Arc<[Arc<dyn EventSource>]>,
+    mode: Mode,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Mode {
+    All,
+    Any,
+}
+
+impl CompositeEvent {
+    /// Creates a `CompositeEvent` that is set while every one of `sources` is set. With no sources
+    /// it's always set. Fails with `AutoResetSource` if one of the sources is an auto-reset event.
+    ///
+    /// # Example
+    /// ```rs
+    /// use casus::CompositeEvent;
+    ///
+    /// let ready = CompositeEvent::all([db_ready.clone(), cache_ready.clone()])?;
+    /// ```
+    pub fn all<S: EventSource + 'static>(
+        sources: impl IntoIterator<Item = S>,
+    ) -> Result<Self, AutoResetSource> {
+        Self::new(sources, Mode::All)
+    }
+
+    /// Creates a `CompositeEvent` that is set while at least one of `sources` is set. With no
+    /// sources it's never set. Fails with `AutoResetSource` if one of the sources is an auto-reset
+    /// event.
+    ///
+    /// # Example
+    /// ```rs
+    /// use casus::CompositeEvent;
+    ///
+    /// let stop = CompositeEvent::any([shutdown.clone(), reload.clone()])?;
+    /// ```
+    pub fn any<S: EventSource + 'static>(
+        sources: impl IntoIterator<Item = S>,
+    ) -> Result<Self, AutoResetSource> {
+        Self::new(sources, Mode::Any)
+    }
+
+    /// Checks if the sources are currently set, all of them or any of them depending on how the
+    /// composite was created
+    ///
+    /// # Example
+    /// ```rs
+    /// if !ready.is_set() {
+    ///     ready.wait().await?;
+    /// }
+    /// ```
+    pub fn is_set(&self) -> bool {
+        match self.mode {
+            Mode::All => self.sources.iter().all(|source| source.is_set()),
+            Mode::Any => self.sources.iter().any(|source| source.is_set()),
+        }
+    }
+
+    /// Waits for the composite to be set, or fails with `EventClosed` if a source it's waiting on
+    /// is closed first. A source that is cleared again before the others are set is waited on
+    /// again, so an `all` composite only completes once every source has been seen set.
+    ///
+    /// Returns `WaitResult::AlreadySet` if the composite was set when the wait started. Otherwise
+    /// an `all` composite returns `WaitResult::Set` and an `any` composite returns how the source
+    /// that completed it was waited on.
+    ///
+    /// # Example
+    /// ```rs
+    /// // will return when the composite is set
+    /// ready.wait().await?;
+    /// ```
+    pub async fn wait(&self) -> Result<WaitResult, EventClosed> {
+        if self.is_set() {
+            return Ok(WaitResult::AlreadySet);
+        }
+        match self.mode {
+            Mode::All => {
+                while let Some(source) = self.sources.iter().find(|source| !source.is_set()) {
+                    source.wait().await?;
+                }
+                Ok(WaitResult::Set)
+            },
+            Mode::Any => {
+                let sources = self
+                    .sources
+                    .iter()
+                    .map(|source| &**source)
+                    .collect::<Vec<_>>();
+                event::wait_any(&sources).await.map(|(_, result)| result)
+            },
+        }
+    }
+
+    /// Returns the sources the composite is made of
+    ///
+    /// # Example
+    /// ```rs
+    /// for source in ready.sources() {
+    ///     println!("set: {}", source.is_set());
+    /// }
+    /// ```
+    pub fn sources(&self) -> &[Arc<dyn EventSource>] {
+        &self.sources
+    }
+
+    fn new<S: EventSource + 'static>(
+        sources: impl IntoIterator<Item = S>,
+        mode: Mode,
+    ) -> Result<Self, AutoResetSource> {
+        let sources = sources
+            .into_iter()
+            .map(|source| {
+                if source.is_auto_reset() {
+                    return Err(AutoResetSource);
+                }
+                Ok(Arc::new(source) as Arc<dyn EventSource>)
+            })
+            .collect::<Result<Arc<[_]>, _>>()?;
+        Ok(Self { sources, mode })
+    }
+}
+
+impl EventSource for CompositeEvent {
+    fn is_set(&self) -> bool {
+        CompositeEvent::is_set(self)
+    }
+
+    fn wait(&self) -> SourceWait<'_> {
+        Box::pin(CompositeEvent::wait(self))
+    }
+}
+
+impl Sealed for CompositeEvent {
+    fn is_auto_reset(&self) -> bool {
+        // sources are checked when the composite is created
+        false
+    }
+}
+
+impl fmt::Debug for CompositeEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CompositeEvent")
+            .field("mode", &self.mode)
+            .field("sources", &self.sources.len())
+            .finish()
+    }
+}
@@ -0,0 +1,201 @@
+use crate::{
+    list::{Cancelled, Registration, WaitList, WaitState, Wakeups},
+    lock::Lock,
+    notify::Notification,
+    MutexGuard,
+};
+
+/// The Condvar primitive lets futures wait for a condition on state behind a mutex without
+/// blocking the thread, works with both casus's `Mutex` and `std::sync::Mutex`.
+///
+/// # Example
+///
+/// ```rs
+/// use casus::{Condvar, Mutex};
+///
+/// let ready = Mutex::new(false);
+/// let condvar = Condvar::new();
+///
+/// // this will block until another task sets `ready` and calls `notify_all`
+/// let guard = condvar.wait_while(ready.lock().await, |ready| !*ready).await;
+/// ```
+///
+/// A waiting future registers itself before it releases the lock, so a notification sent by
+/// whoever takes the lock next is never missed.
+
+#[derive(Debug)]
+pub struct Condvar {
+    waiters: Lock<WaitList<Notification>>,
+}
+
+impl Condvar {
+    /// Creates a new `Condvar` with nothing waiting on it
+    ///
+    /// # Example
+    /// ```rs
+    /// use casus::Condvar;
+    ///
+    /// let condvar = Condvar::new();
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            waiters: Lock::new(WaitList::new()),
+        }
+    }
+
+    /// Releases the lock held by `guard` and waits to be notified, locking the mutex again before
+    /// returning. Like any condvar, it can return without the condition having changed, so it's
+    /// usually called in a loop or through `Condvar::wait_while`.
+    ///
+    /// # Example
+    /// ```rs
+    /// let mut guard = mutex.lock().await;
+    /// while !*guard {
+    ///     guard = condvar.wait(guard).await;
+    /// }
+    /// ```
+    pub async fn wait<'a, T: ?Sized>(&self, guard: MutexGuard<'a, T>) -> MutexGuard<'a, T> {
+        let mutex = MutexGuard::mutex(&guard);
+        self.unlock(guard).wait().await;
+        mutex.lock().await
+    }
+
+    /// Waits to be notified for as long as `condition` returns `true`, returning the guard once it
+    /// returns `false`
+    ///
+    /// # Example
+    /// ```rs
+    /// let guard = condvar.wait_while(mutex.lock().await, |queue| queue.is_empty()).await;
+    /// ```
+    pub async fn wait_while<'a, T: ?Sized>(
+        &self,
+        mut guard: MutexGuard<'a, T>,
+        mut condition: impl FnMut(&mut T) -> bool,
+    ) -> MutexGuard<'a, T> {
+        while condition(&mut guard) {
+            guard = self.wait(guard).await;
+        }
+        guard
+    }
+
+    /// Releases the lock held by a `std::sync::Mutex` guard and waits to be notified, locking
+    /// `mutex` again before returning. `guard` should be a guard of `mutex`, and poisoning is reported
+    /// the same way as `std::sync::Condvar::wait`.
+    ///
+    /// The guard is released when this is called rather than when the future is first polled, so
+    /// the future doesn't hold on to it and can be sent between threads.
+    ///
+    /// # Example
+    /// ```rs
+    /// let mut guard = mutex.lock().unwrap();
+    /// while !*guard {
+    ///     guard = condvar.wait_std(&mutex, guard).await.unwrap();
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn wait_std<'a, T: ?Sized>(
+        &self,
+        mutex: &'a std::sync::Mutex<T>,
+        guard: std::sync::MutexGuard<'a, T>,
+    ) -> impl core::future::Future<Output = std::sync::LockResult<std::sync::MutexGuard<'a, T>>>
+           + use<'_, 'a, T> {
+        let mut registration = self.unlock(guard);
+        async move {
+            registration.wait().await;
+            mutex.lock()
+        }
+    }
+
+    /// Locks `mutex` and waits to be notified like `Condvar::wait_std` for as long as `condition`
+    /// returns `true`, returning the guard once it returns `false`. It takes the mutex instead of a
+    /// guard so that the future can be sent between threads.
+    ///
+    /// # Example
+    /// ```rs
+    /// let guard = condvar
+    ///     .wait_while_std(&mutex, |queue| queue.is_empty())
+    ///     .await
+    ///     .unwrap();
+    /// ```
+    #[cfg(feature = "std")]
+    pub async fn wait_while_std<'a, T: ?Sized>(
+        &self,
+        mutex: &'a std::sync::Mutex<T>,
+        mut condition: impl FnMut(&mut T) -> bool,
+    ) -> std::sync::LockResult<std::sync::MutexGuard<'a, T>> {
+        loop {
+            // the guard is scoped to this block so the future never holds it across the await
+            let mut registration = {
+                let mut guard = mutex.lock()?;
+                if !condition(&mut guard) {
+                    return Ok(guard);
+                }
+                self.unlock(guard)
+            };
+            registration.wait().await;
+        }
+    }
+
+    /// Wakes the future that has been waiting the longest, doing nothing if none are waiting
+    ///
+    /// # Example
+    /// ```rs
+    /// *mutex.lock().await = true;
+    /// condvar.notify_one();
+    /// ```
+    pub fn notify_one(&self) {
+        let mut wakeups = Wakeups::new();
+        self.waiters
+            .lock()
+            .wake_one(Notification::One, &mut wakeups);
+    }
+
+    /// Wakes every waiting future
+    ///
+    /// # Example
+    /// ```rs
+    /// *mutex.lock().await = true;
+    /// condvar.notify_all();
+    /// ```
+    pub fn notify_all(&self) {
+        let mut wakeups = Wakeups::new();
+        self.waiters
+            .lock()
+            .wake_all(Notification::All, &mut wakeups);
+    }
+
+    /// Registers a waiter and only then drops `guard`, so a notification sent by the next holder
+    /// of the lock reaches the waiter
+    fn unlock<G>(&self, guard: G) -> Registration<'_, WaitList<Notification>> {
+        let registration = Registration::new(&self.waiters, &mut self.waiters.lock(), ());
+        drop(guard);
+        registration
+    }
+}
+
+impl Default for Condvar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WaitState for WaitList<Notification> {
+    type Value = Notification;
+    type Data = ();
+
+    fn waiters(&mut self) -> &mut WaitList<Notification> {
+        self
+    }
+
+    fn cancelled(
+        &mut self,
+        cancelled: Cancelled<Notification, ()>,
+        wakeups: &mut Wakeups<Notification>,
+    ) {
+        // a waiter picked by `Condvar::notify_one` that was dropped before it could return hands
+        // the notification on so it isn't lost
+        if let Cancelled::Woken(Notification::One) = cancelled {
+            self.wake_one(Notification::One, wakeups);
+        }
+    }
+}
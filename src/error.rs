@@ -0,0 +1,97 @@
+use alloc::sync::Arc;
+use core::fmt;
+
+/// The error returned when a wait gives up because its timeout elapsed before it completed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TimedOut;
+
+impl fmt::Display for TimedOut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("wait timed out")
+    }
+}
+
+impl core::error::Error for TimedOut {}
+
+/// The error returned when waiting on something that can never complete, because whatever would
+/// have completed it is gone
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Closed;
+
+impl fmt::Display for Closed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("closed")
+    }
+}
+
+impl core::error::Error for Closed {}
+
+/// The error returned when using an `Event` that has been closed, carrying the reason it was
+/// poisoned with, if any
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EventClosed {
+    reason: Option<Arc<str>>,
+}
+
+impl EventClosed {
+    pub(crate) fn new(reason: Option<Arc<str>>) -> Self {
+        Self { reason }
+    }
+
+    /// Returns the reason the event was poisoned with, or `None` if it was closed with
+    /// `Event::close`
+    ///
+    /// # Example
+    /// ```rs
+    /// if let Err(closed) = event.wait().await {
+    ///     eprintln!("event closed: {}", closed.reason().unwrap_or("no reason given"));
+    /// }
+    /// ```
+    pub fn reason(&self) -> Option<&str> {
+        self.reason.as_deref()
+    }
+}
+
+impl fmt::Display for EventClosed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.reason {
+            Some(reason) => write!(f, "event closed: {}", reason),
+            None => f.write_str("event closed"),
+        }
+    }
+}
+
+impl core::error::Error for EventClosed {}
+
+/// The error returned when creating a `CompositeEvent` from an auto-reset event, which can't be a
+/// source since waiting on it would take its release away from its own waiters
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AutoResetSource;
+
+impl fmt::Display for AutoResetSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("composite events can only be made of manual-reset events")
+    }
+}
+
+impl core::error::Error for AutoResetSource {}
+
+/// The error returned when permits can't be acquired from a `Semaphore` without waiting
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TryAcquireError {
+    /// The semaphore has been closed
+    Closed,
+    /// There aren't enough permits available, or other acquires are already waiting for them
+    NoPermits,
+}
+
+impl fmt::Display for TryAcquireError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Closed => f.write_str("semaphore closed"),
+            Self::NoPermits => f.write_str("no permits available"),
+        }
+    }
+}
+
+impl core::error::Error for TryAcquireError {}
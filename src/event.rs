@@ -0,0 +1,753 @@
+use alloc::{boxed::Box, sync::Arc, vec::Vec};
+use core::{
+    future::{poll_fn, Future},
+    ops::Deref,
+    pin::Pin,
+    sync::atomic::{AtomicBool, Ordering},
+    task::{Context, Poll},
+};
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "std")]
+use crate::{blocking, timer};
+use crate::{
+    list::{WaitList, Wakeups},
+    lock::Lock,
+    Closed, EventClosed, Waiter,
+};
+
+/// The Event primitive allows a future to await the completion of an event. Once the event is completed, all futures trying to await it will immediately wake up and any future calls will immediately return until the event is reset.
+///
+/// # Example
+///
+/// ```rs
+/// use casus::Event;
+///
+/// let event = Event::new();
+///
+/// // this will block until Event::set is called elsewhere
+/// event.wait().await?;
+/// ```
+///
+/// An event created with `Event::auto_reset` instead releases a single waiter each time it's set,
+/// in the order they started waiting, and then goes back to being unset.
+///
+/// An event can be closed with `Event::close` or `Event::poison`, after which every current and
+/// future wait fails with `EventClosed` and the event can no longer be set or cleared.
+
+#[derive(Debug)]
+pub struct Event {
+    // the state is only changed while holding the waiters lock, so checking it again under the
+    // lock before registering a waiter can't interleave with `set` or `clear`, but it can still be
+    // read without locking when the event is already set
+    state: AtomicBool,
+    waiters: Lock<Waiters>,
+    auto_reset: bool,
+}
+
+/// How a wait on an `Event` completed
+///
+/// # Example
+///
+/// ```rs
+/// use casus::WaitResult;
+///
+/// match event.wait_timeout(Duration::from_secs(5)).await? {
+///     WaitResult::AlreadySet => println!("the event was already set"),
+///     WaitResult::Set => println!("the event was set while waiting"),
+///     WaitResult::Pulsed => println!("the event was pulsed while waiting"),
+///     WaitResult::TimedOut => println!("the event wasn't set within 5 seconds"),
+/// }
+/// ```
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WaitResult {
+    /// The event was already set when the wait started
+    AlreadySet,
+    /// The event was set by `Event::set` while waiting
+    Set,
+    /// The waiter was woken by `Event::pulse` while waiting
+    Pulsed,
+    /// The timeout elapsed or the deadline passed before the event was set
+    TimedOut,
+}
+
+impl WaitResult {
+    /// Checks if the wait timed out instead of being woken by the event
+    ///
+    /// # Example
+    /// ```rs
+    /// if event.wait_timeout(Duration::from_secs(5)).await?.is_timed_out() {
+    ///     // the event wasn't set within 5 seconds
+    /// }
+    /// ```
+    pub fn is_timed_out(&self) -> bool {
+        *self == Self::TimedOut
+    }
+}
+
+/// The waiters of an event, along with why it was closed once it has been
+#[derive(Debug)]
+struct Waiters {
+    list: WaitList<Result<WaitResult, EventClosed>>,
+    closed: Option<EventClosed>,
+}
+
+impl Waiters {
+    /// Fails with the reason the event was closed, if it has been
+    fn check_open(&self) -> Result<(), EventClosed> {
+        match &self.closed {
+            Some(closed) => Err(closed.clone()),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Event {
+    /// Creates a new `Event`
+    ///
+    /// # Example
+    /// ```rs
+    /// use casus::Event;
+    ///
+    /// let event = Event::new();
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            state: AtomicBool::new(false),
+            waiters: Lock::new(Waiters {
+                list: WaitList::new(),
+                closed: None,
+            }),
+            auto_reset: false,
+        }
+    }
+
+    /// Creates a new auto-reset `Event`, where each `Event::set` releases exactly one waiter and
+    /// leaves the event unset again. If nothing is waiting, the event stays set until the next
+    /// waiter consumes it.
+    ///
+    /// # Example
+    /// ```rs
+    /// use casus::Event;
+    ///
+    /// let event = Event::auto_reset();
+    ///
+    /// event.set()?;
+    /// // returns immediately and resets the event
+    /// event.wait().await?;
+    /// // this will block until Event::set is called again
+    /// event.wait().await?;
+    /// ```
+    pub fn auto_reset() -> Self {
+        Self {
+            auto_reset: true,
+            ..Self::new()
+        }
+    }
+
+    /// Waits for an event to be set, resetting it again if it's an auto-reset event, or fails
+    /// with `EventClosed` if the event is closed before then
+    ///
+    /// # Example
+    /// ```rs
+    /// // will return when `Event::set` is called
+    /// event.wait().await?;
+    /// ```
+    pub fn wait(&self) -> EventWait<'_> {
+        EventWait(Wait::new(self))
+    }
+
+    /// Waits for an event to be set like `Event::wait`, holding on to the event through an `Arc`
+    /// so that the future is `'static` and can be spawned or stored
+    ///
+    /// # Example
+    /// ```rs
+    /// let event = Arc::new(Event::new());
+    ///
+    /// tokio::spawn(event.clone().wait_owned());
+    /// ```
+    pub fn wait_owned(self: Arc<Self>) -> OwnedEventWait {
+        OwnedEventWait(Wait::new(self))
+    }
+
+    /// Waits for any of `sources` to be set, returning the index of the first one that is. Every
+    /// source gets a single registration for the whole wait, which is removed when the future is
+    /// dropped, so an auto-reset event is only reset if its index is the one returned.
+    /// Fails with `EventClosed` if one of the sources is closed first, and never completes if
+    /// `sources` is empty.
+    ///
+    /// # Example
+    /// ```rs
+    /// match Event::wait_any(&[&shutdown, &reload]).await? {
+    ///     0 => println!("shutting down"),
+    ///     _ => println!("reloading"),
+    /// }
+    /// ```
+    pub fn wait_any<'a>(
+        sources: &[&'a dyn EventSource],
+    ) -> impl Future<Output = Result<usize, EventClosed>> + Send + 'a {
+        let wait = wait_any(sources);
+        async move { wait.await.map(|(i, _)| i) }
+    }
+
+    /// Waits for every one of `sources` to have been set, using a single registration per source
+    /// that is removed as soon as that source is set or the future is dropped. Auto-reset events
+    /// are reset as they're waited on, so they don't have to be set at the same time, and if the
+    /// future is dropped or fails before every source is set, the events it reset are set again so
+    /// their releases go to the next waiter instead of being lost. Fails with `EventClosed` if one
+    /// of the sources is closed first.
+    ///
+    /// # Example
+    /// ```rs
+    /// // will return when both `Event::set` calls have been made
+    /// Event::wait_all(&[&config_loaded, &cache_warmed]).await?;
+    /// ```
+    pub fn wait_all<'a>(
+        sources: &[&'a dyn EventSource],
+    ) -> impl Future<Output = Result<(), EventClosed>> + Send + 'a {
+        let mut all = WaitAll {
+            waits: sources
+                .iter()
+                .map(|source| (*source, Some(source.wait())))
+                .collect(),
+            taken: Vec::new(),
+        };
+        poll_fn(move |cx| {
+            let mut pending = false;
+            for (source, slot) in &mut all.waits {
+                let Some(wait) = slot else {
+                    continue;
+                };
+                match wait.as_mut().poll(cx) {
+                    Poll::Ready(Ok(result)) => {
+                        *slot = None;
+                        if source.is_auto_reset()
+                            && matches!(result, WaitResult::AlreadySet | WaitResult::Set)
+                        {
+                            all.taken.push(*source);
+                        }
+                    },
+                    Poll::Ready(Err(closed)) => {
+                        all.hand_back();
+                        return Poll::Ready(Err(closed));
+                    },
+                    Poll::Pending => pending = true,
+                }
+            }
+            if pending {
+                Poll::Pending
+            } else {
+                all.taken.clear();
+                Poll::Ready(Ok(()))
+            }
+        })
+    }
+
+    /// Waits for an event to be set, giving up with `WaitResult::TimedOut` if it isn't set within
+    /// `timeout`
+    ///
+    /// # Example
+    /// ```rs
+    /// if event.wait_timeout(Duration::from_secs(5)).await?.is_timed_out() {
+    ///     // the event wasn't set within 5 seconds
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub async fn wait_timeout(&self, timeout: Duration) -> Result<WaitResult, EventClosed> {
+        timer::timeout(self.wait(), Instant::now().checked_add(timeout))
+            .await
+            .unwrap_or(Ok(WaitResult::TimedOut))
+    }
+
+    /// Waits for an event to be set, giving up with `WaitResult::TimedOut` if it isn't set by
+    /// `deadline`
+    ///
+    /// # Example
+    /// ```rs
+    /// let deadline = Instant::now() + Duration::from_secs(5);
+    /// if event.wait_deadline(deadline).await?.is_timed_out() {
+    ///     // the event wasn't set by the deadline
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub async fn wait_deadline(&self, deadline: Instant) -> Result<WaitResult, EventClosed> {
+        timer::timeout(self.wait(), Some(deadline))
+            .await
+            .unwrap_or(Ok(WaitResult::TimedOut))
+    }
+
+    /// Blocks the current thread until an event is set
+    ///
+    /// # Example
+    /// ```rs
+    /// // will return when `Event::set` is called
+    /// event.wait_blocking()?;
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn wait_blocking(&self) -> Result<WaitResult, EventClosed> {
+        blocking::block_on(self.wait())
+    }
+
+    /// Blocks the current thread until an event is set, giving up with `WaitResult::TimedOut` if it
+    /// isn't set within `timeout`
+    ///
+    /// # Example
+    /// ```rs
+    /// if event.wait_blocking_timeout(Duration::from_secs(5))?.is_timed_out() {
+    ///     // the event wasn't set within 5 seconds
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn wait_blocking_timeout(&self, timeout: Duration) -> Result<WaitResult, EventClosed> {
+        blocking::block_on_deadline(self.wait(), Instant::now().checked_add(timeout))
+            .unwrap_or(Ok(WaitResult::TimedOut))
+    }
+
+    /// Blocks the current thread until an event is set, giving up with `WaitResult::TimedOut` if it
+    /// isn't set by `deadline`
+    ///
+    /// # Example
+    /// ```rs
+    /// let deadline = Instant::now() + Duration::from_secs(5);
+    /// if event.wait_blocking_deadline(deadline)?.is_timed_out() {
+    ///     // the event wasn't set by the deadline
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn wait_blocking_deadline(&self, deadline: Instant) -> Result<WaitResult, EventClosed> {
+        blocking::block_on_deadline(self.wait(), Some(deadline)).unwrap_or(Ok(WaitResult::TimedOut))
+    }
+
+    /// Sets the event and returns all current and future waiters until the event is reset, or
+    /// releases a single waiter if it's an auto-reset event. Fails with `EventClosed` if the event
+    /// has been closed.
+    ///
+    /// # Example
+    /// ```rs
+    /// event.set()?;
+    /// ```
+    pub fn set(&self) -> Result<(), EventClosed> {
+        let mut wakeups = Wakeups::new();
+        let mut waiters = self.waiters.lock();
+        waiters.check_open()?;
+        if self.auto_reset {
+            self.release_one(&mut waiters, &mut wakeups);
+        } else {
+            self.state.store(true, Ordering::Release);
+            waiters.list.wake_all(Ok(WaitResult::Set), &mut wakeups);
+        }
+        Ok(())
+    }
+
+    /// Wakes the waiters that are waiting at the time of the call, or the longest waiting one if
+    /// it's an auto-reset event, without setting the event. The event is left unset afterwards,
+    /// so later calls to `Event::wait` will wait for the next `Event::set` or `Event::pulse`.
+    /// Fails with `EventClosed` if the event has been closed.
+    ///
+    /// # Example
+    /// ```rs
+    /// // wakes up everything currently waiting on the event
+    /// event.pulse()?;
+    /// assert!(!event.is_set());
+    /// ```
+    pub fn pulse(&self) -> Result<(), EventClosed> {
+        let mut wakeups = Wakeups::new();
+        let mut waiters = self.waiters.lock();
+        waiters.check_open()?;
+        self.state.store(false, Ordering::Release);
+        if self.auto_reset {
+            waiters.list.wake_one(Ok(WaitResult::Pulsed), &mut wakeups);
+        } else {
+            waiters.list.wake_all(Ok(WaitResult::Pulsed), &mut wakeups);
+        }
+        Ok(())
+    }
+
+    /// Clears the event, allowing waiters to start waiting again until the event is set. Fails
+    /// with `EventClosed` if the event has been closed.
+    ///
+    /// # Example
+    /// ```rs
+    /// event.clear()?;
+    /// ```
+    pub fn clear(&self) -> Result<(), EventClosed> {
+        let waiters = self.waiters.lock();
+        waiters.check_open()?;
+        self.state.store(false, Ordering::Release);
+        Ok(())
+    }
+
+    /// Closes the event, failing every current and future wait with `EventClosed`. Setting,
+    /// pulsing or clearing the event afterwards fails too. Fails with the original `EventClosed`
+    /// if the event was already closed.
+    ///
+    /// # Example
+    /// ```rs
+    /// // the component that sets the event is shutting down, so stop waiting for it
+    /// event.close()?;
+    /// assert!(event.wait().await.is_err());
+    /// ```
+    pub fn close(&self) -> Result<(), EventClosed> {
+        self.close_with(None)
+    }
+
+    /// Closes the event like `Event::close`, with a reason that every `EventClosed` returned from
+    /// then on carries
+    ///
+    /// # Example
+    /// ```rs
+    /// event.poison("database connection lost")?;
+    /// let closed = event.wait().await.unwrap_err();
+    /// assert_eq!(closed.reason(), Some("database connection lost"));
+    /// ```
+    pub fn poison(&self, reason: impl Into<Arc<str>>) -> Result<(), EventClosed> {
+        self.close_with(Some(reason.into()))
+    }
+
+    /// Checks if the event is set
+    ///
+    /// # Example
+    /// ```rs
+    /// if !event.is_set() {
+    ///     event.wait().await?;
+    /// }
+    pub fn is_set(&self) -> bool {
+        self.state.load(Ordering::Acquire)
+    }
+
+    /// Checks if the event has been closed or poisoned
+    ///
+    /// # Example
+    /// ```rs
+    /// if event.is_closed() {
+    ///     return;
+    /// }
+    /// ```
+    pub fn is_closed(&self) -> bool {
+        self.waiters.lock().closed.is_some()
+    }
+
+    /// Checks if the event is set, resetting it if it's an auto-reset event
+    fn try_acquire(&self) -> bool {
+        if self.auto_reset {
+            self.state
+                .compare_exchange(true, false, Ordering::AcqRel, Ordering::Relaxed)
+                .is_ok()
+        } else {
+            self.state.load(Ordering::Acquire)
+        }
+    }
+
+    /// Releases the longest waiting waiter, or leaves the event set for the next one if nothing is
+    /// waiting
+    fn release_one(
+        &self,
+        waiters: &mut Waiters,
+        wakeups: &mut Wakeups<Result<WaitResult, EventClosed>>,
+    ) {
+        if !waiters.list.wake_one(Ok(WaitResult::Set), wakeups) {
+            self.state.store(true, Ordering::Release);
+        }
+    }
+
+    /// Closes the event with an optional reason, waking every waiter with the resulting error
+    fn close_with(&self, reason: Option<Arc<str>>) -> Result<(), EventClosed> {
+        let mut wakeups = Wakeups::new();
+        let mut waiters = self.waiters.lock();
+        waiters.check_open()?;
+        let closed = EventClosed::new(reason);
+        // the state is cleared so that waits miss the lock-free path and see that it's closed
+        self.state.store(false, Ordering::Release);
+        waiters.list.wake_all(Err(closed.clone()), &mut wakeups);
+        waiters.closed = Some(closed);
+        Ok(())
+    }
+}
+
+impl Default for Event {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Something that can be waited on like an `Event`, so that events and composites of them can be
+/// used interchangeably, as the sources of a `CompositeEvent` or with `Event::wait_any` and
+/// `Event::wait_all`. It's implemented by `Event`, `CompositeEvent` and `Arc`s of them, and can't
+/// be implemented outside of casus.
+///
+/// # Example
+///
+/// ```rs
+/// use casus::{CompositeEvent, Event, EventSource};
+///
+/// let ready = CompositeEvent::all([db_ready.clone(), cache_ready.clone()])?;
+/// let sources: [&dyn EventSource; 2] = [&shutdown, &ready];
+///
+/// // this will block until shutdown is set or both db_ready and cache_ready are
+/// Event::wait_any(&sources).await?;
+/// ```
+pub trait EventSource: sealed::Sealed + Send + Sync {
+    /// Checks if the source is set
+    ///
+    /// # Example
+    /// ```rs
+    /// if !source.is_set() {
+    ///     source.wait().await?;
+    /// }
+    /// ```
+    fn is_set(&self) -> bool;
+
+    /// Waits for the source to be set, like `Event::wait`
+    ///
+    /// # Example
+    /// ```rs
+    /// // will return when the source is set
+    /// source.wait().await?;
+    /// ```
+    fn wait(&self) -> SourceWait<'_>;
+}
+
+/// The future returned by `EventSource::wait`
+pub type SourceWait<'a> =
+    Pin<Box<dyn Future<Output = Result<WaitResult, EventClosed>> + Send + 'a>>;
+
+pub(crate) mod sealed {
+    pub trait Sealed {
+        /// Checks if waiting on the source resets it, which composites can't do without taking
+        /// the release away from the source's own waiters
+        fn is_auto_reset(&self) -> bool;
+
+        /// Gives an auto-reset source back the release a completed wait took from it
+        fn hand_back(&self) {}
+    }
+}
+
+impl EventSource for Event {
+    fn is_set(&self) -> bool {
+        Event::is_set(self)
+    }
+
+    fn wait(&self) -> SourceWait<'_> {
+        Box::pin(Event::wait(self))
+    }
+}
+
+impl sealed::Sealed for Event {
+    fn is_auto_reset(&self) -> bool {
+        self.auto_reset
+    }
+
+    fn hand_back(&self) {
+        // a closed event has nothing to hand the release to
+        let _ = self.set();
+    }
+}
+
+impl<S: EventSource + ?Sized> EventSource for Arc<S> {
+    fn is_set(&self) -> bool {
+        S::is_set(self)
+    }
+
+    fn wait(&self) -> SourceWait<'_> {
+        S::wait(self)
+    }
+}
+
+impl<S: EventSource + ?Sized> sealed::Sealed for Arc<S> {
+    fn is_auto_reset(&self) -> bool {
+        S::is_auto_reset(self)
+    }
+
+    fn hand_back(&self) {
+        S::hand_back(self)
+    }
+}
+
+/// The state of `Event::wait_all`, remembering the auto-reset sources it has reset so they can be
+/// set again if the wait doesn't complete
+struct WaitAll<'a> {
+    waits: Vec<(&'a dyn EventSource, Option<SourceWait<'a>>)>,
+    taken: Vec<&'a dyn EventSource>,
+}
+
+impl WaitAll<'_> {
+    fn hand_back(&mut self) {
+        for source in self.taken.drain(..) {
+            source.hand_back();
+        }
+    }
+}
+
+impl Drop for WaitAll<'_> {
+    fn drop(&mut self) {
+        self.hand_back();
+    }
+}
+
+/// Waits for any of `sources` to be set, returning the index of the first one that is along with
+/// how it was waited on
+pub(crate) fn wait_any<'a>(
+    sources: &[&'a dyn EventSource],
+) -> impl Future<Output = Result<(usize, WaitResult), EventClosed>> + Send + 'a {
+    let mut waits = sources
+        .iter()
+        .map(|source| source.wait())
+        .collect::<Vec<_>>();
+    poll_fn(move |cx| {
+        for (i, wait) in waits.iter_mut().enumerate() {
+            if let Poll::Ready(result) = wait.as_mut().poll(cx) {
+                return Poll::Ready(result.map(|result| (i, result)));
+            }
+        }
+        Poll::Pending
+    })
+}
+
+/// The future returned by `Event::wait`
+///
+/// # Example
+///
+/// ```rs
+/// use casus::{Event, EventWait};
+///
+/// struct Task<'a> {
+///     wait: EventWait<'a>,
+/// }
+///
+/// let task = Task { wait: event.wait() };
+/// task.wait.await?;
+/// ```
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct EventWait<'a>(Wait<&'a Event>);
+
+impl Future for EventWait<'_> {
+    type Output = Result<WaitResult, EventClosed>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.0.poll(cx)
+    }
+}
+
+/// The future returned by `Event::wait_owned`, which keeps its event alive through an `Arc`
+///
+/// # Example
+///
+/// ```rs
+/// use casus::{Event, OwnedEventWait};
+///
+/// let event = Arc::new(Event::new());
+/// let wait: OwnedEventWait = event.clone().wait_owned();
+/// tokio::spawn(wait);
+/// ```
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct OwnedEventWait(Wait<Arc<Event>>);
+
+impl Future for OwnedEventWait {
+    type Output = Result<WaitResult, EventClosed>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.0.poll(cx)
+    }
+}
+
+/// A wait on an event, registering a waiter the first time the event isn't set when polled and
+/// removing it again when dropped, so cancelled waits don't leave their registration behind
+#[derive(Debug)]
+struct Wait<E: Deref<Target = Event>> {
+    event: E,
+    registration: Option<(u64, Waiter<Result<WaitResult, EventClosed>>)>,
+}
+
+impl<E: Deref<Target = Event>> Wait<E> {
+    fn new(event: E) -> Self {
+        Self {
+            event,
+            registration: None,
+        }
+    }
+
+    fn poll(&mut self, cx: &mut Context<'_>) -> Poll<Result<WaitResult, EventClosed>> {
+        let (_, waiter) = match &mut self.registration {
+            Some(registration) => registration,
+            None => {
+                if self.event.try_acquire() {
+                    return Poll::Ready(Ok(WaitResult::AlreadySet));
+                }
+                let mut waiters = self.event.waiters.lock();
+                waiters.check_open()?;
+                if self.event.try_acquire() {
+                    return Poll::Ready(Ok(WaitResult::AlreadySet));
+                }
+                self.registration.insert(waiters.list.register())
+            },
+        };
+        match Pin::new(waiter).poll(cx) {
+            Poll::Ready(Ok(result)) => Poll::Ready(result),
+            Poll::Ready(Err(Closed)) => {
+                unreachable!("events hold on to a waiter's handle until they wake it")
+            },
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl<E: Deref<Target = Event>> Drop for Wait<E> {
+    fn drop(&mut self) {
+        let Some((key, waiter)) = &self.registration else {
+            return;
+        };
+        let event = &*self.event;
+        let mut wakeups = Wakeups::new();
+        let mut waiters = event.waiters.lock();
+        if !waiters.list.remove(*key)
+            && event.auto_reset
+            && waiter.try_take() == Some(Ok(WaitResult::Set))
+            && waiters.closed.is_none()
+        {
+            // this waiter was released by a set but dropped before it could return, so hand the
+            // release on to the next waiter instead of losing it
+            event.release_one(&mut waiters, &mut wakeups);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::{
+        future::Future,
+        pin::pin,
+        task::{Context, Waker},
+    };
+
+    use super::*;
+
+    fn poll_once<F: Future>(fut: Pin<&mut F>) -> Poll<F::Output> {
+        fut.poll(&mut Context::from_waker(Waker::noop()))
+    }
+
+    #[test]
+    fn dropped_waits_deregister() {
+        let event = Event::new();
+        for _ in 0..100 {
+            let mut wait = pin!(event.wait());
+            assert!(poll_once(wait.as_mut()).is_pending());
+            assert_eq!(event.waiters.lock().list.len(), 1);
+        }
+        assert_eq!(event.waiters.lock().list.len(), 0);
+    }
+
+    #[test]
+    fn set_empties_the_waiter_list() {
+        for event in [Event::new(), Event::auto_reset()] {
+            let mut wait = pin!(event.wait());
+            assert!(poll_once(wait.as_mut()).is_pending());
+            event.set().unwrap();
+            assert_eq!(event.waiters.lock().list.len(), 0);
+            assert_eq!(poll_once(wait.as_mut()), Poll::Ready(Ok(WaitResult::Set)));
+        }
+    }
+}
@@ -0,0 +1,253 @@
+use core::{
+    cell::UnsafeCell,
+    fmt,
+    future::Future,
+    mem::MaybeUninit,
+    sync::atomic::{AtomicU8, Ordering},
+};
+
+use crate::{
+    list::{Registration, WaitList, Wakeups},
+    lock::Lock,
+};
+
+const EMPTY: u8 = 0;
+const INITIALIZING: u8 = 1;
+const SET: u8 = 2;
+
+/// The Latch primitive is a cell that can be set once, after which every future waiting on it and
+/// every later call gets a reference to the value.
+///
+/// # Example
+///
+/// ```rs
+/// use casus::Latch;
+///
+/// let latch = Latch::new();
+///
+/// // this will block until Latch::set is called elsewhere
+/// let value = latch.get().await;
+/// ```
+///
+/// `Latch::get_or_init` makes it an async `OnceCell`, the first caller initializes the value and
+/// every other caller waits for it instead of initializing it again.
+pub struct Latch<T> {
+    // moves from EMPTY to INITIALIZING outside of the waiters lock, but only moves on to SET, or
+    // back to EMPTY if initializing is abandoned, while holding it
+    state: AtomicU8,
+    value: UnsafeCell<MaybeUninit<T>>,
+    waiters: Lock<WaitList<()>>,
+}
+
+// SAFETY: the value is only written once, before the state is set to SET, and only read after
+// observing SET, so sharing a latch shares `&T` between threads and may drop `T` on another thread
+unsafe impl<T: Send + Sync> Sync for Latch<T> {}
+unsafe impl<T: Send> Send for Latch<T> {}
+
+impl<T> Latch<T> {
+    /// Creates a new, unset `Latch`
+    ///
+    /// # Example
+    /// ```rs
+    /// use casus::Latch;
+    ///
+    /// let latch = Latch::<u32>::new();
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            state: AtomicU8::new(EMPTY),
+            value: UnsafeCell::new(MaybeUninit::uninit()),
+            waiters: Lock::new(WaitList::new()),
+        }
+    }
+
+    /// Sets the latch to `value`, waking every future waiting on it, or hands `value` back if the
+    /// latch has already been set or is being initialized
+    ///
+    /// # Example
+    /// ```rs
+    /// latch.set(1).unwrap();
+    /// assert_eq!(latch.set(2), Err(2));
+    /// ```
+    pub fn set(&self, value: T) -> Result<(), T> {
+        if self
+            .state
+            .compare_exchange(EMPTY, INITIALIZING, Ordering::Acquire, Ordering::Acquire)
+            .is_err()
+        {
+            return Err(value);
+        }
+        self.publish(value);
+        Ok(())
+    }
+
+    /// Returns the value if the latch has been set, without waiting
+    ///
+    /// # Example
+    /// ```rs
+    /// if let Some(value) = latch.try_get() {
+    ///     println!("already set to {value}");
+    /// }
+    /// ```
+    pub fn try_get(&self) -> Option<&T> {
+        if self.state.load(Ordering::Acquire) == SET {
+            // SAFETY: the value was written before the state was set to SET and is never written
+            // again
+            Some(unsafe { (*self.value.get()).assume_init_ref() })
+        } else {
+            None
+        }
+    }
+
+    /// Waits for the latch to be set, returning a reference to its value
+    ///
+    /// # Example
+    /// ```rs
+    /// // will return when `Latch::set` is called
+    /// let value = latch.get().await;
+    /// ```
+    pub async fn get(&self) -> &T {
+        loop {
+            match self.state.load(Ordering::Acquire) {
+                SET => return self.try_get().unwrap(),
+                state => self.wait_while(state).await,
+            }
+        }
+    }
+
+    /// Returns the value of the latch, initializing it with the future returned by `init` if it
+    /// hasn't been set. Only one caller initializes the latch at a time, the rest wait for it to
+    /// finish. If the initializing future is dropped before it completes, one of the waiting
+    /// callers takes over instead.
+    ///
+    /// # Example
+    /// ```rs
+    /// let connection = latch.get_or_init(|| Connection::open(url)).await;
+    /// ```
+    pub async fn get_or_init<F>(&self, init: impl FnOnce() -> F) -> &T
+    where
+        F: Future<Output = T>,
+    {
+        let mut init = Some(init);
+        loop {
+            match self.state.load(Ordering::Acquire) {
+                SET => return self.try_get().unwrap(),
+                EMPTY
+                    if self
+                        .state
+                        .compare_exchange(EMPTY, INITIALIZING, Ordering::Acquire, Ordering::Acquire)
+                        .is_ok() =>
+                {
+                    let guard = Initializing { latch: self };
+                    // the closure is only taken by the caller that wins the exchange, which
+                    // returns right after
+                    let value = init.take().unwrap()().await;
+                    core::mem::forget(guard);
+                    self.publish(value);
+                    return self.try_get().unwrap();
+                },
+                state => self.wait_while(state).await,
+            }
+        }
+    }
+
+    /// Checks if the latch has been set
+    ///
+    /// # Example
+    /// ```rs
+    /// if !latch.is_set() {
+    ///     latch.get().await;
+    /// }
+    /// ```
+    pub fn is_set(&self) -> bool {
+        self.state.load(Ordering::Acquire) == SET
+    }
+
+    /// Consumes the latch, returning its value if it has been set
+    ///
+    /// # Example
+    /// ```rs
+    /// let value = latch.into_inner();
+    /// ```
+    pub fn into_inner(mut self) -> Option<T> {
+        if *self.state.get_mut() == SET {
+            *self.state.get_mut() = EMPTY;
+            // SAFETY: the value was written before the state was set to SET, and resetting the
+            // state stops it from being dropped again
+            Some(unsafe { self.value.get_mut().assume_init_read() })
+        } else {
+            None
+        }
+    }
+
+    /// Writes the value of a latch this caller moved to INITIALIZING and wakes every waiter
+    fn publish(&self, value: T) {
+        // SAFETY: only the caller that moved the state to INITIALIZING writes the value, and
+        // nothing reads it until the state is SET
+        unsafe { (*self.value.get()).write(value) };
+        let mut wakeups = Wakeups::new();
+        let mut waiters = self.waiters.lock();
+        self.state.store(SET, Ordering::Release);
+        waiters.wake_all((), &mut wakeups);
+    }
+
+    /// Waits for the state to move on from `state`
+    async fn wait_while(&self, state: u8) {
+        let mut registration = {
+            let mut waiters = self.waiters.lock();
+            if self.state.load(Ordering::Acquire) != state {
+                return;
+            }
+            Registration::new(&self.waiters, &mut waiters, ())
+        };
+        registration.wait().await;
+    }
+}
+
+impl<T> Default for Latch<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> From<T> for Latch<T> {
+    fn from(value: T) -> Self {
+        Self {
+            state: AtomicU8::new(SET),
+            value: UnsafeCell::new(MaybeUninit::new(value)),
+            waiters: Lock::new(WaitList::new()),
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Latch<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Latch")
+            .field("value", &self.try_get())
+            .finish()
+    }
+}
+
+impl<T> Drop for Latch<T> {
+    fn drop(&mut self) {
+        if *self.state.get_mut() == SET {
+            // SAFETY: the value was written before the state was set to SET
+            unsafe { self.value.get_mut().assume_init_drop() };
+        }
+    }
+}
+
+/// Moves a latch back to EMPTY and wakes its waiters if the future initializing it is dropped, so
+/// one of them can take over
+struct Initializing<'a, T> {
+    latch: &'a Latch<T>,
+}
+
+impl<T> Drop for Initializing<'_, T> {
+    fn drop(&mut self) {
+        let mut wakeups = Wakeups::new();
+        let mut waiters = self.latch.waiters.lock();
+        self.latch.state.store(EMPTY, Ordering::Release);
+        waiters.wake_all((), &mut wakeups);
+    }
+}
@@ -1,4 +1,4 @@
-//! Casus is a simple library containing a handful of useful generic async primitives. At present, it contains `Event` and `Waiter` primitives.
+//! Casus is a simple library containing a handful of useful generic async primitives. At present, it contains `Event`, `ValueEvent`, `Watch`, `Latch`, `Semaphore`, `Mutex`, `RwLock`, `Barrier`, `Phaser`, `WaitGroup`, `CountdownLatch`, `Notify`, `Condvar`, `CancellationToken`, `CompositeEvent` and `Waiter` primitives, along with oneshot, mpsc and broadcast channels.
 //!
 //! ## Event
 //!
@@ -10,7 +10,173 @@
 //! let event = Event::new();
 //!
 //! // this will block until Event::set is called elsewhere
-//! event.wait().await;
+//! event.wait().await?;
+//! ```
+//!
+//! ## ValueEvent
+//!
+//! The ValueEvent primitive is an Event that is set with a value, every future waiting on it is woken up with a clone of the value and later waits return it until the event is cleared.
+//!
+//! ```rs
+//! use casus::ValueEvent;
+//!
+//! let event = ValueEvent::new();
+//!
+//! // this will block until ValueEvent::set is called elsewhere
+//! let value = event.wait().await;
+//! ```
+//!
+//! ## Watch
+//!
+//! The Watch primitive holds a value that changes over time, futures can wait for the next change or for the value to satisfy a predicate.
+//!
+//! ```rs
+//! use casus::Watch;
+//!
+//! let mut watch = Watch::new(0);
+//!
+//! // this will block until Watch::send is called elsewhere with a value above 10
+//! watch.wait_until(|v| *v > 10).await;
+//! ```
+//!
+//! ## Latch
+//!
+//! The Latch primitive is a cell that can be set once, every future waiting on it and every later call gets a reference to the value.
+//!
+//! ```rs
+//! use casus::Latch;
+//!
+//! let latch = Latch::new();
+//!
+//! // this will block until Latch::set is called elsewhere
+//! let value = latch.get().await;
+//! ```
+//!
+//! ## Semaphore
+//!
+//! The Semaphore primitive limits how many futures can do something at once, acquires are served in the order they started waiting so large ones aren't starved.
+//!
+//! ```rs
+//! use casus::Semaphore;
+//!
+//! let semaphore = Semaphore::new(4);
+//!
+//! // this will block until a permit is available
+//! let permit = semaphore.acquire(1).await?;
+//! ```
+//!
+//! ## Mutex and RwLock
+//!
+//! The Mutex and RwLock primitives are async locks whose guards can be held across an `.await`, futures lock them in the order they started waiting.
+//!
+//! ```rs
+//! use casus::{Mutex, RwLock};
+//!
+//! let mutex = Mutex::new(0);
+//! let lock = RwLock::new(0);
+//!
+//! // these will block until the locks are unlocked elsewhere
+//! *mutex.lock().await += 1;
+//! *lock.write().await += 1;
+//! ```
+//!
+//! ## Barrier and Phaser
+//!
+//! The Barrier primitive makes a fixed number of futures wait for each other and tells one of them it's the leader, the Phaser primitive does the same for parties that can register and deregister between phases.
+//!
+//! ```rs
+//! use casus::{Barrier, Phaser};
+//!
+//! let barrier = Barrier::new(4);
+//! let phaser = Phaser::new(4);
+//!
+//! // these will block until the other 3 futures have arrived
+//! barrier.wait().await;
+//! phaser.arrive_and_wait().await;
+//! ```
+//!
+//! ## WaitGroup and CountdownLatch
+//!
+//! The WaitGroup primitive waits for every piece of work that holds one of its guards to finish, the CountdownLatch primitive waits for a fixed number of things to happen.
+//!
+//! ```rs
+//! use casus::{CountdownLatch, WaitGroup};
+//!
+//! let group = WaitGroup::new();
+//! let latch = CountdownLatch::new(3);
+//!
+//! let guard = group.add();
+//! // these will block until the guard is dropped and `count_down` is called 3 times
+//! group.wait().await;
+//! latch.wait().await;
+//! ```
+//!
+//! ## Notify
+//!
+//! The Notify primitive wakes one waiter or every waiter without holding any state besides a single permit, which `notify_one` stores when nothing is waiting.
+//!
+//! ```rs
+//! use casus::Notify;
+//!
+//! let notify = Notify::new();
+//!
+//! // this will block until `notify_one` or `notify_waiters` is called
+//! notify.notified().await;
+//! ```
+//!
+//! ## Condvar
+//!
+//! The Condvar primitive lets futures wait for a condition on state behind either a casus `Mutex` or a `std::sync::Mutex` without blocking the thread.
+//!
+//! ```rs
+//! use casus::{Condvar, Mutex};
+//!
+//! let ready = Mutex::new(false);
+//! let condvar = Condvar::new();
+//!
+//! // this will block until another task sets `ready` and calls `notify_all`
+//! let guard = condvar.wait_while(ready.lock().await, |ready| !*ready).await;
+//! ```
+//!
+//! ## Channels
+//!
+//! The `channel` module contains oneshot, mpsc and broadcast channels, bounded mpsc sends wait for space and broadcast receivers that fall behind are told how many values they missed.
+//!
+//! ```rs
+//! use casus::channel::mpsc;
+//!
+//! let (sender, mut receiver) = mpsc::channel(16);
+//!
+//! // this will block while the channel is full
+//! sender.send(5).await?;
+//! assert_eq!(receiver.recv().await, Some(5));
+//! ```
+//!
+//! ## CancellationToken
+//!
+//! The CancellationToken primitive signals cancellation to every future waiting on it, and cancelling a token cancels every child token created from it.
+//!
+//! ```rs
+//! use casus::CancellationToken;
+//!
+//! let token = CancellationToken::new();
+//! let child = token.child_token();
+//!
+//! // this will return `None` if `token` or `child` is cancelled before `work` completes
+//! let result = child.run_until_cancelled(work()).await;
+//! ```
+//!
+//! ## CompositeEvent
+//!
+//! The CompositeEvent primitive is set while all, or any, of the events it's made of are set, and follows them as they're set and cleared. Composites can be made of other composites, and waited on along with events by `Event::wait_any` and `Event::wait_all`.
+//!
+//! ```rs
+//! use casus::CompositeEvent;
+//!
+//! let ready = CompositeEvent::all([db_ready.clone(), cache_ready.clone()])?;
+//!
+//! // this will block until both events are set
+//! ready.wait().await?;
 //! ```
 //!
 //! ## Waiter
@@ -20,181 +186,57 @@
 //! ```rs
 //! use casus::Waiter;
 //!
-//! let waiter = Waiter::new();
+//! let (handle, waiter) = Waiter::new();
 //!
-//! // this will block until Event::wake is called elsewhere
+//! // this will block until WakeHandle::wake is called elsewhere
 //! waiter.await;
 //! ```
 
-use std::{
-    future::Future,
-    sync::{Arc, Mutex, RwLock},
-    task::{Poll, Waker},
-};
-/// The Event primitive allows a future to await the completion of an event. Once the event is completed, all futures trying to await it will immediately wake up and any future calls will immediately return until the event is reset.
-///
-/// # Example
-///
-/// ```rs
-/// use casus::Event;
-///
-/// let event = Event::new();
-///
-/// // this will block until Event::set is called elsewhere
-/// event.wait().await;
-/// ```
-
-#[derive(Debug)]
-pub struct Event {
-    state: RwLock<bool>,
-    waiters: Mutex<Vec<Waiter<()>>>,
-}
-
-impl Event {
-    /// Creates a new `Event`
-    ///
-    /// # Example
-    /// ```rs
-    /// use casus::Event;
-    ///
-    /// let event = Event::new();
-    /// ```
-    pub fn new() -> Self {
-        Self {
-            state: RwLock::new(false),
-            waiters: Mutex::new(vec![]),
-        }
-    }
-
-    /// Waits for an event to be set
-    ///
-    /// # Example
-    /// ```rs
-    /// // will return when `Event::set` is called
-    /// event.wait().await;
-    /// ```
-    pub async fn wait(&self) -> bool {
-        let state = *self.state.read().unwrap();
-        if !state {
-            let fut = Waiter::new();
-            {
-                let mut waiters = self.waiters.lock().unwrap();
-                waiters.push(fut.clone());
-            }
-            fut.await;
-        }
-        true
-    }
-
-    /// Sets the event and returns all current and future waiters until the event is reset
-    ///
-    /// # Example
-    /// ```rs
-    /// event.set();
-    /// ```
-    pub fn set(&self) {
-        {
-            let mut state = self.state.write().unwrap();
-            *state = true;
-        }
-        for i in self.waiters.lock().unwrap().iter() {
-            i.wake(());
-        }
-    }
-
-    /// Clears the event, allowing waiters to start waiting again until the event is set
-    ///
-    /// # Example
-    /// ```rs
-    /// event.clear();
-    /// ```
-    pub fn clear(&self) {
-        *self.state.write().unwrap() = false;
-    }
+#![cfg_attr(not(feature = "std"), no_std)]
 
-    /// Checks if the event is set
-    ///
-    /// # Example
-    /// ```rs
-    /// if !event.is_set() {
-    ///     event.wait().await;
-    /// }
-    pub fn is_set(&self) -> bool {
-        *self.state.read().unwrap()
-    }
-}
+extern crate alloc;
 
-impl Default for Event {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-/// The Waiter primitive simply waits to be woken up with it's return value.
-///
-/// # Example
-///
-/// ```rs
-/// use casus::Waiter;
-///
-/// let waiter = Waiter::new();
-///
-/// // this will block until Event::wake is called elsewhere
-/// waiter.await;
-/// ```
+mod atomic_waker;
+mod barrier;
+#[cfg(feature = "std")]
+mod blocking;
+mod cancellation_token;
+pub mod channel;
+mod composite_event;
+mod condvar;
+mod error;
+mod event;
+mod latch;
+mod list;
+mod lock;
+mod mutex;
+mod notify;
+mod phaser;
+mod rwlock;
+mod semaphore;
+#[cfg(feature = "std")]
+mod timer;
+mod value_event;
+mod wait_group;
+mod waiter;
+mod watch;
 
-#[derive(Clone, Debug)]
-pub struct Waiter<T>(
-    #[allow(clippy::type_complexity)] Arc<Mutex<(bool, Option<Waker>, Option<T>)>>,
-);
-
-impl<T> Waiter<T> {
-    /// Creates a new `Waiter`
-    ///
-    /// # Example
-    /// ```rs
-    /// use casus::Waiter;
-    ///
-    /// let waiter = Waiter::new();
-    /// ```
-    pub fn new() -> Self {
-        Self(Arc::new(Mutex::new((false, None, None))))
-    }
-
-    /// Wakes up the waiter with `T` as the return value, meaning anything awaiting the waiter will return the value T
-    ///
-    /// # Example
-    /// ```
-    /// waiter.wake(T)
-    /// ```
-    pub fn wake(&self, v: T) {
-        let mut state = self.0.lock().unwrap();
-        state.0 = true;
-        state.2 = Some(v);
-        if let Some(waker) = state.1.take() {
-            waker.wake();
-        }
-    }
-}
-
-impl<T> Default for Waiter<T> {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
-impl<T> Future for Waiter<T> {
-    type Output = T;
-
-    fn poll(
-        self: std::pin::Pin<&mut Self>,
-        cx: &mut std::task::Context<'_>,
-    ) -> std::task::Poll<Self::Output> {
-        let mut state = self.0.lock().unwrap();
-        if state.0 {
-            Poll::Ready(state.2.take().unwrap())
-        } else {
-            state.1 = Some(cx.waker().clone());
-            Poll::Pending
-        }
-    }
-}
+pub use barrier::{Barrier, BarrierWaitResult};
+pub use cancellation_token::CancellationToken;
+pub use composite_event::CompositeEvent;
+pub use condvar::Condvar;
+pub use error::{AutoResetSource, Closed, EventClosed, TimedOut, TryAcquireError};
+pub use event::{Event, EventSource, EventWait, OwnedEventWait, SourceWait, WaitResult};
+pub use latch::Latch;
+pub use mutex::{MappedMutexGuard, Mutex, MutexGuard, OwnedMappedMutexGuard, OwnedMutexGuard};
+pub use notify::{Notified, Notify};
+pub use phaser::Phaser;
+pub use rwlock::{
+    MappedRwLockWriteGuard, OwnedMappedRwLockWriteGuard, OwnedRwLockReadGuard,
+    OwnedRwLockWriteGuard, RwLock, RwLockReadGuard, RwLockWriteGuard,
+};
+pub use semaphore::{OwnedSemaphorePermit, Semaphore, SemaphorePermit};
+pub use value_event::{ValueEvent, ValueEventWait};
+pub use wait_group::{CountdownLatch, OwnedWaitGroupGuard, WaitGroup, WaitGroupGuard};
+pub use waiter::{SharedWaiter, Waiter, WakeHandle};
+pub use watch::{Watch, WatchRef};
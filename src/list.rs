@@ -0,0 +1,259 @@
+use alloc::{collections::VecDeque, vec::Vec};
+use core::{
+    fmt,
+    future::{poll_fn, Future},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use crate::{lock::Lock, Closed, Waiter, WakeHandle};
+
+/// A FIFO list of registered waiters, each identified by a key so that it can deregister itself
+/// when the future waiting on it is dropped, and carrying some data about what it's waiting for
+#[derive(Debug)]
+pub(crate) struct WaitList<T, D = ()> {
+    next_key: u64,
+    entries: VecDeque<(u64, D, WakeHandle<T>)>,
+}
+
+impl<T> WaitList<T> {
+    /// Registers a new waiter at the back of the list, returning its key and the waiter to await
+    pub(crate) fn register(&mut self) -> (u64, Waiter<T>) {
+        self.register_with(())
+    }
+}
+
+impl<T, D> WaitList<T, D> {
+    /// Creates a new, empty `WaitList`
+    pub(crate) fn new() -> Self {
+        Self {
+            next_key: 0,
+            entries: VecDeque::new(),
+        }
+    }
+
+    /// Registers a new waiter carrying `data` at the back of the list, returning its key and the
+    /// waiter to await
+    pub(crate) fn register_with(&mut self, data: D) -> (u64, Waiter<T>) {
+        let key = self.next_key;
+        self.next_key += 1;
+        let (handle, waiter) = Waiter::new();
+        self.entries.push_back((key, data, handle));
+        (key, waiter)
+    }
+
+    /// Removes the waiter with the given key, returning whether it was still registered
+    pub(crate) fn remove(&mut self, key: u64) -> bool {
+        self.remove_entry(key).is_some()
+    }
+
+    /// Removes the waiter with the given key, returning its data if it was still registered
+    pub(crate) fn remove_entry(&mut self, key: u64) -> Option<D> {
+        // keys are handed out in increasing order, so the list is always sorted by key
+        let i = self.entries.binary_search_by_key(&key, |(k, ..)| *k).ok()?;
+        self.entries.remove(i).map(|(_, data, _)| data)
+    }
+
+    /// Returns the number of registered waiters
+    #[cfg(test)]
+    pub(crate) fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns the data of the waiter that has been registered the longest
+    pub(crate) fn front(&self) -> Option<&D> {
+        self.entries.front().map(|(_, data, _)| data)
+    }
+
+    /// Wakes the waiter that has been registered the longest with `v` once `wakeups` is dropped,
+    /// returning whether it was woken or there was nothing to wake
+    pub(crate) fn wake_front(&mut self, v: T, wakeups: &mut Wakeups<T>) -> bool {
+        match self.entries.pop_front() {
+            Some((.., handle)) => wakeups.deliver(handle, v).is_ok(),
+            None => false,
+        }
+    }
+
+    /// Wakes the waiter that has been registered the longest with `v` once `wakeups` is dropped,
+    /// returning whether there was a waiter to wake
+    pub(crate) fn wake_one(&mut self, mut v: T, wakeups: &mut Wakeups<T>) -> bool {
+        while let Some((.., handle)) = self.entries.pop_front() {
+            match wakeups.deliver(handle, v) {
+                Ok(()) => return true,
+                // the waiter was dropped without deregistering, so try the next one
+                Err(returned) => v = returned,
+            }
+        }
+        false
+    }
+
+    /// Wakes every registered waiter with a clone of `v` once `wakeups` is dropped, leaving the
+    /// list empty
+    pub(crate) fn wake_all(&mut self, v: T, wakeups: &mut Wakeups<T>)
+    where
+        T: Clone,
+    {
+        for (.., handle) in self.entries.drain(..) {
+            let _ = wakeups.deliver(handle, v.clone());
+        }
+    }
+}
+
+/// Waiters that were given their values while a lock was held, which are woken when this is
+/// dropped. It's created before taking the lock so that it's dropped after the lock is released,
+/// since a waker could poll or drop a future that takes the lock again.
+#[must_use]
+pub(crate) struct Wakeups<T>(Vec<WakeHandle<T>>);
+
+impl<T> Wakeups<T> {
+    pub(crate) fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    fn deliver(&mut self, handle: WakeHandle<T>, v: T) -> Result<(), T> {
+        handle.deliver(v)?;
+        self.0.push(handle);
+        Ok(())
+    }
+}
+
+impl<T> Drop for Wakeups<T> {
+    fn drop(&mut self) {
+        for handle in self.0.drain(..) {
+            handle.wake_waiters();
+        }
+    }
+}
+
+/// State behind a `Lock` that holds the `WaitList` a `Registration` is registered in
+pub(crate) trait WaitState {
+    /// What the waiters are woken with
+    type Value;
+    /// The data the waiters are registered with
+    type Data;
+
+    fn waiters(&mut self) -> &mut WaitList<Self::Value, Self::Data>;
+
+    /// Called with the lock held when a registration is dropped before its waiter returned, waking
+    /// anything it has to through `wakeups`
+    fn cancelled(
+        &mut self,
+        _cancelled: Cancelled<Self::Value, Self::Data>,
+        _wakeups: &mut Wakeups<Self::Value>,
+    ) {
+    }
+}
+
+impl WaitState for WaitList<()> {
+    type Value = ();
+    type Data = ();
+
+    fn waiters(&mut self) -> &mut WaitList<()> {
+        self
+    }
+}
+
+/// How far a cancelled registration got before it was cancelled
+pub(crate) enum Cancelled<T, D> {
+    /// It was still registered, with the data it was registered with
+    Registered(D),
+    /// It had been woken with a value it never returned
+    Woken(T),
+}
+
+/// A waiter registered in the `WaitList` of some state, which doesn't hold on to the state so it
+/// can be kept anywhere, but has to be passed to `Registered::cancel` if it's given up on before
+/// returning. `Registration` does that when dropped.
+pub(crate) struct Registered<S: WaitState> {
+    key: u64,
+    waiter: Waiter<S::Value>,
+}
+
+impl<S: WaitState> Registered<S> {
+    /// Registers a waiter carrying `data` in `state`
+    pub(crate) fn new(state: &mut S, data: S::Data) -> Self {
+        let (key, waiter) = state.waiters().register_with(data);
+        Self { key, waiter }
+    }
+
+    /// Takes the value the waiter was woken with, if it has been
+    pub(crate) fn try_take(&self) -> Option<S::Value> {
+        self.waiter.try_take()
+    }
+
+    pub(crate) fn poll(&mut self, cx: &mut Context<'_>) -> Poll<S::Value> {
+        match Pin::new(&mut self.waiter).poll(cx) {
+            Poll::Ready(Ok(v)) => Poll::Ready(v),
+            Poll::Ready(Err(Closed)) => {
+                // the waiter is cancelled before the state is dropped, so its list can't be gone
+                unreachable!("wait lists hold on to a waiter's handle until they wake it")
+            },
+            Poll::Pending => Poll::Pending,
+        }
+    }
+
+    /// Removes the waiter from `state`, which has to be the state it was registered in
+    pub(crate) fn cancel(&self, state: &mut S, wakeups: &mut Wakeups<S::Value>) {
+        let cancelled = match state.waiters().remove_entry(self.key) {
+            Some(data) => Cancelled::Registered(data),
+            None => match self.waiter.try_take() {
+                Some(v) => Cancelled::Woken(v),
+                None => return,
+            },
+        };
+        state.cancelled(cancelled, wakeups);
+    }
+}
+
+impl<S: WaitState> fmt::Debug for Registered<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Registered")
+            .field("key", &self.key)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A waiter registered in the `WaitList` of some locked state, which removes itself when dropped so
+/// cancelled waits don't leave their registration behind
+pub(crate) struct Registration<'a, S: WaitState> {
+    state: &'a Lock<S>,
+    registered: Registered<S>,
+}
+
+impl<'a, S: WaitState> Registration<'a, S> {
+    /// Registers a waiter carrying `data` in `locked`, which has to be the locked value of `state`
+    pub(crate) fn new(state: &'a Lock<S>, locked: &mut S, data: S::Data) -> Self {
+        Self {
+            state,
+            registered: Registered::new(locked, data),
+        }
+    }
+
+    /// Takes the value the waiter was woken with, if it has been
+    pub(crate) fn try_take(&self) -> Option<S::Value> {
+        self.registered.try_take()
+    }
+
+    pub(crate) fn poll(&mut self, cx: &mut Context<'_>) -> Poll<S::Value> {
+        self.registered.poll(cx)
+    }
+
+    pub(crate) async fn wait(&mut self) -> S::Value {
+        poll_fn(|cx| self.poll(cx)).await
+    }
+}
+
+impl<S: WaitState> Drop for Registration<'_, S> {
+    fn drop(&mut self) {
+        let mut wakeups = Wakeups::new();
+        self.registered.cancel(&mut self.state.lock(), &mut wakeups);
+    }
+}
+
+impl<S: WaitState> fmt::Debug for Registration<'_, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Registration")
+            .field("key", &self.registered.key)
+            .finish_non_exhaustive()
+    }
+}
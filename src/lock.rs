@@ -0,0 +1,149 @@
+//! The lock guarding the state of casus's primitives, which is a `std::sync::Mutex` with the `std`
+//! feature and a spin lock without it. It's only ever held for short, non-blocking critical
+//! sections, so spinning is fine.
+
+use core::fmt;
+#[cfg(not(feature = "std"))]
+use core::{
+    cell::UnsafeCell,
+    hint,
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+#[cfg(feature = "std")]
+pub(crate) type LockGuard<'a, T> = std::sync::MutexGuard<'a, T>;
+
+pub(crate) struct Lock<T> {
+    #[cfg(feature = "std")]
+    inner: std::sync::Mutex<T>,
+    #[cfg(not(feature = "std"))]
+    locked: AtomicBool,
+    #[cfg(not(feature = "std"))]
+    value: UnsafeCell<T>,
+}
+
+#[cfg(feature = "std")]
+impl<T> Lock<T> {
+    pub(crate) const fn new(value: T) -> Self {
+        Self {
+            inner: std::sync::Mutex::new(value),
+        }
+    }
+
+    pub(crate) fn lock(&self) -> LockGuard<'_, T> {
+        // nothing that can panic runs while holding the lock, so the state is still consistent
+        self.inner
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    pub(crate) fn try_lock(&self) -> Option<LockGuard<'_, T>> {
+        match self.inner.try_lock() {
+            Ok(guard) => Some(guard),
+            Err(std::sync::TryLockError::Poisoned(e)) => Some(e.into_inner()),
+            Err(std::sync::TryLockError::WouldBlock) => None,
+        }
+    }
+}
+
+// SAFETY: the value is only ever accessed through a `LockGuard`, and only one can exist at a time
+#[cfg(not(feature = "std"))]
+unsafe impl<T: Send> Send for Lock<T> {}
+#[cfg(not(feature = "std"))]
+unsafe impl<T: Send> Sync for Lock<T> {}
+
+#[cfg(not(feature = "std"))]
+impl<T> Lock<T> {
+    pub(crate) const fn new(value: T) -> Self {
+        Self {
+            locked: AtomicBool::new(false),
+            value: UnsafeCell::new(value),
+        }
+    }
+
+    pub(crate) fn lock(&self) -> LockGuard<'_, T> {
+        while self
+            .locked
+            .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            while self.locked.load(Ordering::Relaxed) {
+                hint::spin_loop();
+            }
+        }
+        LockGuard {
+            lock: self,
+            _not_send_sync: PhantomData,
+        }
+    }
+
+    pub(crate) fn try_lock(&self) -> Option<LockGuard<'_, T>> {
+        self.locked
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .ok()
+            .map(|_| LockGuard {
+                lock: self,
+                _not_send_sync: PhantomData,
+            })
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Lock<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.try_lock() {
+            Some(value) => f.debug_struct("Lock").field("value", &&*value).finish(),
+            None => f.write_str("Lock { <locked> }"),
+        }
+    }
+}
+
+#[cfg(not(feature = "std"))]
+pub(crate) struct LockGuard<'a, T> {
+    lock: &'a Lock<T>,
+    // like `std::sync::MutexGuard` the guard is `!Send`, and only `Sync` if `T` is
+    _not_send_sync: PhantomData<*const ()>,
+}
+
+// SAFETY: sharing the guard only shares `&T`, which is fine as long as `T` is `Sync`
+#[cfg(not(feature = "std"))]
+unsafe impl<T: Sync> Sync for LockGuard<'_, T> {}
+
+#[cfg(not(feature = "std"))]
+impl<T> Deref for LockGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: holding the guard gives exclusive access to the value
+        unsafe { &*self.lock.value.get() }
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl<T> DerefMut for LockGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // SAFETY: holding the guard gives exclusive access to the value
+        unsafe { &mut *self.lock.value.get() }
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl<T> Drop for LockGuard<'_, T> {
+    fn drop(&mut self) {
+        self.lock.locked.store(false, Ordering::Release);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cell::Cell;
+
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+
+    use super::LockGuard;
+
+    assert_impl_all!(LockGuard<'static, u32>: Sync);
+    assert_not_impl_any!(LockGuard<'static, Cell<u32>>: Sync);
+    assert_not_impl_any!(LockGuard<'static, u32>: Send);
+}
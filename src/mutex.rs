@@ -0,0 +1,427 @@
+use alloc::sync::Arc;
+use core::{
+    cell::UnsafeCell,
+    fmt,
+    marker::PhantomData,
+    mem::{self, ManuallyDrop},
+    ops::{Deref, DerefMut},
+};
+
+use crate::{Closed, Semaphore};
+
+/// The Mutex primitive is an async mutual exclusion lock, whose guard can be held across an
+/// `.await` without blocking the thread.
+///
+/// # Example
+///
+/// ```rs
+/// use casus::Mutex;
+///
+/// let mutex = Mutex::new(0);
+///
+/// // this will block until the mutex is unlocked elsewhere
+/// let mut value = mutex.lock().await;
+/// *value += 1;
+/// ```
+///
+/// The mutex is built on a `Semaphore` with a single permit, so futures lock it in the order they
+/// started waiting.
+pub struct Mutex<T: ?Sized> {
+    semaphore: Semaphore,
+    value: UnsafeCell<T>,
+}
+
+// SAFETY: the value is only accessed through a guard, and only one guard can exist at a time
+unsafe impl<T: ?Sized + Send> Send for Mutex<T> {}
+unsafe impl<T: ?Sized + Send> Sync for Mutex<T> {}
+
+impl<T> Mutex<T> {
+    /// Creates a new, unlocked `Mutex` holding `value`
+    ///
+    /// # Example
+    /// ```rs
+    /// use casus::Mutex;
+    ///
+    /// let mutex = Mutex::new(Vec::new());
+    /// ```
+    pub fn new(value: T) -> Self {
+        Self {
+            semaphore: Semaphore::new(1),
+            value: UnsafeCell::new(value),
+        }
+    }
+
+    /// Consumes the mutex, returning the value it holds
+    ///
+    /// # Example
+    /// ```rs
+    /// let value = mutex.into_inner();
+    /// ```
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+}
+
+impl<T: ?Sized> Mutex<T> {
+    /// Waits for the mutex to be unlocked and locks it, returning a guard that unlocks it when
+    /// dropped
+    ///
+    /// # Example
+    /// ```rs
+    /// let mut value = mutex.lock().await;
+    /// *value += 1;
+    /// ```
+    pub async fn lock(&self) -> MutexGuard<'_, T> {
+        acquired(self.semaphore.acquire(1).await).forget();
+        MutexGuard {
+            mutex: self,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Locks the mutex like `Mutex::lock`, holding on to the mutex through an `Arc` so that the
+    /// guard is `'static`
+    ///
+    /// # Example
+    /// ```rs
+    /// let mut value = mutex.clone().lock_owned().await;
+    /// tokio::spawn(async move {
+    ///     *value += 1;
+    /// });
+    /// ```
+    pub async fn lock_owned(self: Arc<Self>) -> OwnedMutexGuard<T> {
+        acquired(self.semaphore.acquire(1).await).forget();
+        OwnedMutexGuard { mutex: self }
+    }
+
+    /// Locks the mutex without waiting, returning `None` if it's locked or other futures are
+    /// already waiting to lock it
+    ///
+    /// # Example
+    /// ```rs
+    /// if let Some(mut value) = mutex.try_lock() {
+    ///     *value += 1;
+    /// }
+    /// ```
+    pub fn try_lock(&self) -> Option<MutexGuard<'_, T>> {
+        self.semaphore.try_acquire(1).ok()?.forget();
+        Some(MutexGuard {
+            mutex: self,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Locks the mutex without waiting like `Mutex::try_lock`, holding on to the mutex through an
+    /// `Arc` so that the guard is `'static`
+    ///
+    /// # Example
+    /// ```rs
+    /// if let Some(mut value) = mutex.clone().try_lock_owned() {
+    ///     *value += 1;
+    /// }
+    /// ```
+    pub fn try_lock_owned(self: Arc<Self>) -> Option<OwnedMutexGuard<T>> {
+        self.semaphore.try_acquire(1).ok()?.forget();
+        Some(OwnedMutexGuard { mutex: self })
+    }
+
+    /// Returns a mutable reference to the value, which needs no locking since the mutex is
+    /// borrowed mutably
+    ///
+    /// # Example
+    /// ```rs
+    /// *mutex.get_mut() += 1;
+    /// ```
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+
+    /// Unlocks the mutex, for a guard that is being dropped
+    fn unlock(&self) {
+        self.semaphore.add_permits(1);
+    }
+}
+
+impl<T: Default> Default for Mutex<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T> From<T> for Mutex<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for Mutex<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.try_lock() {
+            Some(value) => f.debug_struct("Mutex").field("value", &&*value).finish(),
+            None => f.write_str("Mutex { <locked> }"),
+        }
+    }
+}
+
+/// Unwraps the result of acquiring a lock's semaphore, which is never closed
+pub(crate) fn acquired<P>(permit: Result<P, Closed>) -> P {
+    match permit {
+        Ok(permit) => permit,
+        Err(Closed) => unreachable!("the semaphores of locks are never closed"),
+    }
+}
+
+/// A guard that holds a `Mutex` locked until it's dropped
+#[must_use = "the mutex is unlocked as soon as the guard is dropped"]
+pub struct MutexGuard<'a, T: ?Sized> {
+    mutex: &'a Mutex<T>,
+    // sharing the guard shares `&T`, so it's only `Sync` if `T` is
+    _marker: PhantomData<&'a mut T>,
+}
+
+impl<'a, T: ?Sized> MutexGuard<'a, T> {
+    /// Returns the mutex the guard is locking
+    ///
+    /// # Example
+    /// ```rs
+    /// let mutex = MutexGuard::mutex(&guard);
+    /// ```
+    pub fn mutex(this: &Self) -> &'a Mutex<T> {
+        this.mutex
+    }
+
+    /// Makes a guard for a part of the locked value, which keeps the mutex locked until it's
+    /// dropped
+    ///
+    /// # Example
+    /// ```rs
+    /// let name = MutexGuard::map(mutex.lock().await, |user| &mut user.name);
+    /// ```
+    pub fn map<U: ?Sized>(this: Self, f: impl FnOnce(&mut T) -> &mut U) -> MappedMutexGuard<'a, U> {
+        let mutex = this.mutex;
+        // SAFETY: the guard gives exclusive access to the value until the mapped guard unlocks the
+        // mutex
+        let value = f(unsafe { &mut *mutex.value.get() });
+        // only forgotten once `f` has returned, so the mutex is still unlocked if it panics
+        mem::forget(this);
+        MappedMutexGuard {
+            semaphore: &mutex.semaphore,
+            value,
+        }
+    }
+}
+
+impl<T: ?Sized> Deref for MutexGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: holding the guard gives exclusive access to the value
+        unsafe { &*self.mutex.value.get() }
+    }
+}
+
+impl<T: ?Sized> DerefMut for MutexGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        // SAFETY: holding the guard gives exclusive access to the value
+        unsafe { &mut *self.mutex.value.get() }
+    }
+}
+
+impl<T: ?Sized> Drop for MutexGuard<'_, T> {
+    fn drop(&mut self) {
+        self.mutex.unlock();
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for MutexGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+/// A guard that holds a `Mutex` in an `Arc` locked until it's dropped
+#[must_use = "the mutex is unlocked as soon as the guard is dropped"]
+pub struct OwnedMutexGuard<T: ?Sized> {
+    mutex: Arc<Mutex<T>>,
+}
+
+// SAFETY: sharing the guard shares `&T`, so it's only `Sync` if `T` is
+unsafe impl<T: ?Sized + Send + Sync> Sync for OwnedMutexGuard<T> {}
+
+impl<T: ?Sized> OwnedMutexGuard<T> {
+    /// Returns the mutex the guard is locking
+    ///
+    /// # Example
+    /// ```rs
+    /// let mutex = OwnedMutexGuard::mutex(&guard).clone();
+    /// ```
+    pub fn mutex(this: &Self) -> &Arc<Mutex<T>> {
+        &this.mutex
+    }
+
+    /// Makes a guard for a part of the locked value, which keeps the mutex locked until it's
+    /// dropped
+    ///
+    /// # Example
+    /// ```rs
+    /// let name = OwnedMutexGuard::map(mutex.lock_owned().await, |user| &mut user.name);
+    /// ```
+    pub fn map<U: ?Sized>(
+        this: Self,
+        f: impl FnOnce(&mut T) -> &mut U,
+    ) -> OwnedMappedMutexGuard<T, U> {
+        // SAFETY: the guard gives exclusive access to the value until the mapped guard unlocks the
+        // mutex
+        let value = f(unsafe { &mut *this.mutex.value.get() }) as *mut U;
+        // only forgotten once `f` has returned, so the mutex is still unlocked if it panics
+        let this = ManuallyDrop::new(this);
+        // SAFETY: the guard is never dropped, so moving the mutex out of it leaves the mutex
+        // locked for the mapped guard
+        let mutex = unsafe { core::ptr::read(&this.mutex) };
+        OwnedMappedMutexGuard { mutex, value }
+    }
+}
+
+impl<T: ?Sized> Deref for OwnedMutexGuard<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: holding the guard gives exclusive access to the value
+        unsafe { &*self.mutex.value.get() }
+    }
+}
+
+impl<T: ?Sized> DerefMut for OwnedMutexGuard<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        // SAFETY: holding the guard gives exclusive access to the value
+        unsafe { &mut *self.mutex.value.get() }
+    }
+}
+
+impl<T: ?Sized> Drop for OwnedMutexGuard<T> {
+    fn drop(&mut self) {
+        self.mutex.unlock();
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for OwnedMutexGuard<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+/// A guard for a part of the value held by a `Mutex`, made with `MutexGuard::map`
+#[must_use = "the mutex is unlocked as soon as the guard is dropped"]
+pub struct MappedMutexGuard<'a, U: ?Sized> {
+    semaphore: &'a Semaphore,
+    value: &'a mut U,
+}
+
+impl<'a, U: ?Sized> MappedMutexGuard<'a, U> {
+    /// Makes a guard for a part of the mapped value, which keeps the mutex locked until it's
+    /// dropped
+    ///
+    /// # Example
+    /// ```rs
+    /// let first = MappedMutexGuard::map(names, |names| &mut names[0]);
+    /// ```
+    pub fn map<V: ?Sized>(this: Self, f: impl FnOnce(&mut U) -> &mut V) -> MappedMutexGuard<'a, V> {
+        let semaphore = this.semaphore;
+        // SAFETY: the guard is forgotten once `f` returns, leaving the new guard as the only way to
+        // reach the value
+        let value = f(unsafe { &mut *(&mut *this.value as *mut U) });
+        // only forgotten once `f` has returned, so the mutex is still unlocked if it panics
+        mem::forget(this);
+        MappedMutexGuard { semaphore, value }
+    }
+}
+
+impl<U: ?Sized> Deref for MappedMutexGuard<'_, U> {
+    type Target = U;
+
+    fn deref(&self) -> &U {
+        self.value
+    }
+}
+
+impl<U: ?Sized> DerefMut for MappedMutexGuard<'_, U> {
+    fn deref_mut(&mut self) -> &mut U {
+        self.value
+    }
+}
+
+impl<U: ?Sized> Drop for MappedMutexGuard<'_, U> {
+    fn drop(&mut self) {
+        self.semaphore.add_permits(1);
+    }
+}
+
+impl<U: ?Sized + fmt::Debug> fmt::Debug for MappedMutexGuard<'_, U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+/// A guard for a part of the value held by a `Mutex` in an `Arc`, made with
+/// `OwnedMutexGuard::map`
+#[must_use = "the mutex is unlocked as soon as the guard is dropped"]
+pub struct OwnedMappedMutexGuard<T: ?Sized, U: ?Sized> {
+    mutex: Arc<Mutex<T>>,
+    value: *mut U,
+}
+
+// SAFETY: the guard gives exclusive access to a part of the value, as if it was a `&mut U`
+unsafe impl<T: ?Sized + Send, U: ?Sized + Send> Send for OwnedMappedMutexGuard<T, U> {}
+unsafe impl<T: ?Sized + Send, U: ?Sized + Sync> Sync for OwnedMappedMutexGuard<T, U> {}
+
+impl<T: ?Sized, U: ?Sized> OwnedMappedMutexGuard<T, U> {
+    /// Makes a guard for a part of the mapped value, which keeps the mutex locked until it's
+    /// dropped
+    ///
+    /// # Example
+    /// ```rs
+    /// let first = OwnedMappedMutexGuard::map(names, |names| &mut names[0]);
+    /// ```
+    pub fn map<V: ?Sized>(
+        this: Self,
+        f: impl FnOnce(&mut U) -> &mut V,
+    ) -> OwnedMappedMutexGuard<T, V> {
+        // SAFETY: the guard gives exclusive access to the value until the new guard unlocks the
+        // mutex
+        let value = f(unsafe { &mut *this.value }) as *mut V;
+        // only forgotten once `f` has returned, so the mutex is still unlocked if it panics
+        let this = ManuallyDrop::new(this);
+        // SAFETY: the guard is never dropped, so moving the mutex out of it leaves the mutex
+        // locked for the new guard
+        let mutex = unsafe { core::ptr::read(&this.mutex) };
+        OwnedMappedMutexGuard { mutex, value }
+    }
+}
+
+impl<T: ?Sized, U: ?Sized> Deref for OwnedMappedMutexGuard<T, U> {
+    type Target = U;
+
+    fn deref(&self) -> &U {
+        // SAFETY: holding the guard gives exclusive access to the value
+        unsafe { &*self.value }
+    }
+}
+
+impl<T: ?Sized, U: ?Sized> DerefMut for OwnedMappedMutexGuard<T, U> {
+    fn deref_mut(&mut self) -> &mut U {
+        // SAFETY: holding the guard gives exclusive access to the value
+        unsafe { &mut *self.value }
+    }
+}
+
+impl<T: ?Sized, U: ?Sized> Drop for OwnedMappedMutexGuard<T, U> {
+    fn drop(&mut self) {
+        self.mutex.unlock();
+    }
+}
+
+impl<T: ?Sized, U: ?Sized + fmt::Debug> fmt::Debug for OwnedMappedMutexGuard<T, U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
@@ -0,0 +1,226 @@
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use crate::{
+    list::{Cancelled, Registration, WaitList, WaitState, Wakeups},
+    lock::Lock,
+};
+
+/// The Notify primitive wakes futures without carrying any state of its own besides a single
+/// stored permit. `Notify::notify_one` wakes one waiter, or stores a permit for the next one if
+/// none are waiting, and `Notify::notify_waiters` wakes every current waiter.
+///
+/// # Example
+///
+/// ```rs
+/// use casus::Notify;
+///
+/// let notify = Notify::new();
+///
+/// // register before checking the condition, so a notification in between isn't missed
+/// let mut notified = notify.notified();
+/// notified.enable();
+/// if !ready() {
+///     // this will block until `notify_one` or `notify_waiters` is called
+///     notified.await;
+/// }
+/// ```
+
+#[derive(Debug)]
+pub struct Notify {
+    state: Lock<State>,
+}
+
+#[derive(Debug)]
+struct State {
+    permit: bool,
+    // counts the calls to `notify_waiters`, so a `Notified` created before one can tell it missed
+    // it even if it hadn't registered yet
+    generation: u64,
+    waiters: WaitList<Notification>,
+}
+
+/// How a waiter was woken, so one that is dropped after being woken knows whether the wakeup was
+/// meant for it alone and has to be handed on
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Notification {
+    One,
+    All,
+}
+
+impl State {
+    fn notify_one(&mut self, wakeups: &mut Wakeups<Notification>) {
+        if !self.waiters.wake_one(Notification::One, wakeups) {
+            self.permit = true;
+        }
+    }
+}
+
+impl Notify {
+    /// Creates a new `Notify` with no permit stored
+    ///
+    /// # Example
+    /// ```rs
+    /// use casus::Notify;
+    ///
+    /// let notify = Notify::new();
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            state: Lock::new(State {
+                permit: false,
+                generation: 0,
+                waiters: WaitList::new(),
+            }),
+        }
+    }
+
+    /// Returns a future that completes once this `Notify` is notified. The future takes part in
+    /// `Notify::notify_waiters` calls from the moment it's created, and in `Notify::notify_one`
+    /// calls once it's polled or `Notified::enable` is called.
+    ///
+    /// # Example
+    /// ```rs
+    /// // will return when notified
+    /// notify.notified().await;
+    /// ```
+    pub fn notified(&self) -> Notified<'_> {
+        Notified {
+            notify: self,
+            generation: self.state.lock().generation,
+            registration: None,
+            notified: false,
+        }
+    }
+
+    /// Wakes the waiter that has been waiting the longest, or stores a permit that completes the
+    /// next wait straight away if nothing is waiting. At most one permit is stored.
+    ///
+    /// # Example
+    /// ```rs
+    /// notify.notify_one();
+    /// ```
+    pub fn notify_one(&self) {
+        let mut wakeups = Wakeups::new();
+        self.state.lock().notify_one(&mut wakeups);
+    }
+
+    /// Wakes every current waiter, along with every `Notified` that has been created but not
+    /// polled yet, without storing a permit
+    ///
+    /// # Example
+    /// ```rs
+    /// notify.notify_waiters();
+    /// ```
+    pub fn notify_waiters(&self) {
+        let mut wakeups = Wakeups::new();
+        let mut state = self.state.lock();
+        state.generation = state.generation.wrapping_add(1);
+        state.waiters.wake_all(Notification::All, &mut wakeups);
+    }
+}
+
+impl Default for Notify {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The future returned by `Notify::notified`
+///
+/// # Example
+///
+/// ```rs
+/// use casus::{Notified, Notify};
+///
+/// let mut notified: Notified = notify.notified();
+/// notified.enable();
+/// notified.await;
+/// ```
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Notified<'a> {
+    notify: &'a Notify,
+    generation: u64,
+    registration: Option<Registration<'a, State>>,
+    notified: bool,
+}
+
+impl Notified<'_> {
+    /// Registers the future with its `Notify` without polling it, so that `Notify::notify_one`
+    /// calls from this point on are delivered to it. Returns whether it has already been notified.
+    ///
+    /// # Example
+    /// ```rs
+    /// let mut notified = notify.notified();
+    /// notified.enable();
+    /// // a notification sent while checking the condition isn't missed
+    /// if !ready() {
+    ///     notified.await;
+    /// }
+    /// ```
+    pub fn enable(&mut self) -> bool {
+        if self.notified {
+            return true;
+        }
+        if let Some(registration) = &self.registration {
+            self.notified = registration.try_take().is_some();
+            return self.notified;
+        }
+        let mut state = self.notify.state.lock();
+        if state.generation != self.generation {
+            self.notified = true;
+        } else if state.permit {
+            state.permit = false;
+            self.notified = true;
+        } else {
+            self.registration = Some(Registration::new(&self.notify.state, &mut state, ()));
+        }
+        self.notified
+    }
+}
+
+impl Future for Notified<'_> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let this = &mut *self;
+        if this.enable() {
+            return Poll::Ready(());
+        }
+        let Some(registration) = &mut this.registration else {
+            unreachable!("enabled futures are either notified or registered")
+        };
+        match registration.poll(cx) {
+            Poll::Ready(_) => {
+                this.notified = true;
+                Poll::Ready(())
+            },
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl WaitState for State {
+    type Value = Notification;
+    type Data = ();
+
+    fn waiters(&mut self) -> &mut WaitList<Notification> {
+        &mut self.waiters
+    }
+
+    fn cancelled(
+        &mut self,
+        cancelled: Cancelled<Notification, ()>,
+        wakeups: &mut Wakeups<Notification>,
+    ) {
+        // a waiter picked by `notify_one` that was dropped before it could return hands the
+        // notification on instead of losing it
+        if let Cancelled::Woken(Notification::One) = cancelled {
+            self.notify_one(wakeups);
+        }
+    }
+}
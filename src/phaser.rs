@@ -0,0 +1,244 @@
+use crate::{
+    list::{Cancelled, Registration, WaitList, WaitState, Wakeups},
+    lock::Lock,
+};
+
+/// The Phaser primitive is a reusable barrier whose parties can change between phases. Each phase
+/// ends once every registered party has arrived, and the phaser moves on to the next one.
+///
+/// # Example
+///
+/// ```rs
+/// use casus::Phaser;
+///
+/// let phaser = Phaser::new(3);
+///
+/// // this will block until the other 2 parties have arrived
+/// let phase = phaser.arrive_and_wait().await;
+///
+/// // this party won't take part in later phases
+/// phaser.arrive_and_deregister();
+/// ```
+
+#[derive(Debug)]
+pub struct Phaser {
+    state: Lock<State>,
+}
+
+#[derive(Debug)]
+struct State {
+    phase: u64,
+    parties: usize,
+    arrived: usize,
+    // woken with the phase that was started once the current phase ends, carrying whether the
+    // waiter arrived at the phase it's waiting on
+    waiters: WaitList<u64, bool>,
+}
+
+impl State {
+    /// Counts a party arriving at the current phase
+    fn arrive(&mut self) {
+        assert!(
+            self.arrived < self.parties,
+            "more parties arrived than are registered with the phaser"
+        );
+        self.arrived += 1;
+    }
+
+    /// Ends the current phase if every party has arrived, returning the phase that was started
+    fn try_advance(&mut self, wakeups: &mut Wakeups<u64>) -> Option<u64> {
+        if self.parties == 0 || self.arrived < self.parties {
+            return None;
+        }
+        self.phase += 1;
+        self.arrived = 0;
+        self.waiters.wake_all(self.phase, wakeups);
+        Some(self.phase)
+    }
+}
+
+impl Phaser {
+    /// Creates a new `Phaser` with `parties` parties registered, starting at phase 0
+    ///
+    /// # Example
+    /// ```rs
+    /// use casus::Phaser;
+    ///
+    /// let phaser = Phaser::new(3);
+    /// ```
+    pub fn new(parties: usize) -> Self {
+        Self {
+            state: Lock::new(State {
+                phase: 0,
+                parties,
+                arrived: 0,
+                waiters: WaitList::new(),
+            }),
+        }
+    }
+
+    /// Registers a new party, which has to arrive before the current phase can end, returning the
+    /// current phase
+    ///
+    /// # Example
+    /// ```rs
+    /// let phase = phaser.register();
+    /// ```
+    pub fn register(&self) -> u64 {
+        let mut state = self.state.lock();
+        state.parties += 1;
+        state.phase
+    }
+
+    /// Deregisters a party that hasn't arrived in the current phase, ending the phase if every
+    /// remaining party has arrived, and returns the current phase
+    ///
+    /// # Panics
+    /// Panics if no parties are registered
+    ///
+    /// # Example
+    /// ```rs
+    /// phaser.deregister();
+    /// ```
+    pub fn deregister(&self) -> u64 {
+        let mut wakeups = Wakeups::new();
+        let mut state = self.state.lock();
+        assert!(
+            state.parties > 0,
+            "no parties are registered with the phaser"
+        );
+        state.parties -= 1;
+        state.try_advance(&mut wakeups).unwrap_or(state.phase)
+    }
+
+    /// Arrives at the current phase without waiting for it to end, returning the phase that was
+    /// arrived at
+    ///
+    /// # Panics
+    /// Panics if every registered party has already arrived, such as when none are registered
+    ///
+    /// # Example
+    /// ```rs
+    /// let phase = phaser.arrive();
+    /// ```
+    pub fn arrive(&self) -> u64 {
+        let mut wakeups = Wakeups::new();
+        let mut state = self.state.lock();
+        let phase = state.phase;
+        state.arrive();
+        state.try_advance(&mut wakeups);
+        phase
+    }
+
+    /// Arrives at the current phase and deregisters, so this party doesn't take part in later
+    /// phases, returning the phase that was arrived at
+    ///
+    /// # Panics
+    /// Panics if no parties are registered
+    ///
+    /// # Example
+    /// ```rs
+    /// phaser.arrive_and_deregister();
+    /// ```
+    pub fn arrive_and_deregister(&self) -> u64 {
+        let mut wakeups = Wakeups::new();
+        let mut state = self.state.lock();
+        assert!(
+            state.parties > 0,
+            "no parties are registered with the phaser"
+        );
+        let phase = state.phase;
+        state.parties -= 1;
+        state.try_advance(&mut wakeups);
+        phase
+    }
+
+    /// Arrives at the current phase and waits for every other party to arrive, returning the phase
+    /// that was started. A future that is dropped while waiting takes back its arrival.
+    ///
+    /// # Panics
+    /// Panics if every registered party has already arrived, such as when none are registered
+    ///
+    /// # Example
+    /// ```rs
+    /// // will return when every registered party has arrived
+    /// let next = phaser.arrive_and_wait().await;
+    /// ```
+    pub async fn arrive_and_wait(&self) -> u64 {
+        let mut registration = {
+            let mut wakeups = Wakeups::new();
+            let mut state = self.state.lock();
+            state.arrive();
+            if let Some(phase) = state.try_advance(&mut wakeups) {
+                return phase;
+            }
+            Registration::new(&self.state, &mut state, true)
+        };
+        registration.wait().await
+    }
+
+    /// Waits for `phase` to end without arriving at it, returning the phase that is current once
+    /// it has
+    ///
+    /// # Example
+    /// ```rs
+    /// let phase = phaser.arrive();
+    /// phaser.wait_for_advance(phase).await;
+    /// ```
+    pub async fn wait_for_advance(&self, phase: u64) -> u64 {
+        let mut registration = {
+            let mut state = self.state.lock();
+            if state.phase != phase {
+                return state.phase;
+            }
+            Registration::new(&self.state, &mut state, false)
+        };
+        registration.wait().await
+    }
+
+    /// Returns the current phase
+    ///
+    /// # Example
+    /// ```rs
+    /// println!("in phase {}", phaser.phase());
+    /// ```
+    pub fn phase(&self) -> u64 {
+        self.state.lock().phase
+    }
+
+    /// Returns the number of registered parties
+    ///
+    /// # Example
+    /// ```rs
+    /// println!("{} parties", phaser.parties());
+    /// ```
+    pub fn parties(&self) -> usize {
+        self.state.lock().parties
+    }
+
+    /// Returns the number of parties that have arrived at the current phase
+    ///
+    /// # Example
+    /// ```rs
+    /// println!("{} of {} parties arrived", phaser.arrived(), phaser.parties());
+    /// ```
+    pub fn arrived(&self) -> usize {
+        self.state.lock().arrived
+    }
+}
+
+impl WaitState for State {
+    type Value = u64;
+    type Data = bool;
+
+    fn waiters(&mut self) -> &mut WaitList<u64, bool> {
+        &mut self.waiters
+    }
+
+    fn cancelled(&mut self, cancelled: Cancelled<u64, bool>, _wakeups: &mut Wakeups<u64>) {
+        // a cancelled wait takes back its arrival if it arrived at the phase it was waiting on
+        if let Cancelled::Registered(true) = cancelled {
+            self.arrived -= 1;
+        }
+    }
+}
@@ -0,0 +1,565 @@
+use alloc::sync::Arc;
+use core::{
+    cell::UnsafeCell,
+    fmt,
+    marker::PhantomData,
+    mem::{self, ManuallyDrop},
+    ops::{Deref, DerefMut},
+};
+
+use crate::{mutex::acquired, Semaphore};
+
+/// The number of permits a writer acquires, which is also the most readers that can hold the lock
+/// at once
+const MAX_READS: usize = usize::MAX >> 3;
+
+/// The RwLock primitive is an async reader-writer lock, which can be read by many futures at once
+/// or written by one, and whose guards can be held across an `.await` without blocking the thread.
+///
+/// # Example
+///
+/// ```rs
+/// use casus::RwLock;
+///
+/// let lock = RwLock::new(0);
+///
+/// // these will block until the lock isn't being written elsewhere
+/// let first = lock.read().await;
+/// let second = lock.read().await;
+/// ```
+///
+/// The lock is built on a `Semaphore`, with readers acquiring one permit and writers acquiring all
+/// of them, so futures lock it in the order they started waiting. Once a writer is waiting, later
+/// readers wait behind it instead of starving it.
+pub struct RwLock<T: ?Sized> {
+    semaphore: Semaphore,
+    value: UnsafeCell<T>,
+}
+
+// SAFETY: the value is only accessed through guards, either one writer or any number of readers
+unsafe impl<T: ?Sized + Send> Send for RwLock<T> {}
+unsafe impl<T: ?Sized + Send + Sync> Sync for RwLock<T> {}
+
+impl<T> RwLock<T> {
+    /// Creates a new, unlocked `RwLock` holding `value`
+    ///
+    /// # Example
+    /// ```rs
+    /// use casus::RwLock;
+    ///
+    /// let lock = RwLock::new(Vec::new());
+    /// ```
+    pub fn new(value: T) -> Self {
+        Self {
+            semaphore: Semaphore::new(MAX_READS),
+            value: UnsafeCell::new(value),
+        }
+    }
+
+    /// Consumes the lock, returning the value it holds
+    ///
+    /// # Example
+    /// ```rs
+    /// let value = lock.into_inner();
+    /// ```
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+}
+
+impl<T: ?Sized> RwLock<T> {
+    /// Waits for the lock not to be written and locks it for reading, returning a guard that
+    /// unlocks it when dropped
+    ///
+    /// # Example
+    /// ```rs
+    /// let value = lock.read().await;
+    /// println!("{}", *value);
+    /// ```
+    pub async fn read(&self) -> RwLockReadGuard<'_, T> {
+        acquired(self.semaphore.acquire(1).await).forget();
+        RwLockReadGuard {
+            semaphore: &self.semaphore,
+            // SAFETY: holding a read permit means nothing is writing the value
+            value: unsafe { &*self.value.get() },
+        }
+    }
+
+    /// Locks the lock for reading like `RwLock::read`, holding on to the lock through an `Arc` so
+    /// that the guard is `'static`
+    ///
+    /// # Example
+    /// ```rs
+    /// let value = lock.clone().read_owned().await;
+    /// ```
+    pub async fn read_owned(self: Arc<Self>) -> OwnedRwLockReadGuard<T> {
+        acquired(self.semaphore.acquire(1).await).forget();
+        OwnedRwLockReadGuard {
+            value: self.value.get(),
+            lock: self,
+        }
+    }
+
+    /// Locks the lock for reading without waiting, returning `None` if it's being written or a
+    /// writer is waiting for it
+    ///
+    /// # Example
+    /// ```rs
+    /// if let Some(value) = lock.try_read() {
+    ///     println!("{}", *value);
+    /// }
+    /// ```
+    pub fn try_read(&self) -> Option<RwLockReadGuard<'_, T>> {
+        self.semaphore.try_acquire(1).ok()?.forget();
+        Some(RwLockReadGuard {
+            semaphore: &self.semaphore,
+            // SAFETY: holding a read permit means nothing is writing the value
+            value: unsafe { &*self.value.get() },
+        })
+    }
+
+    /// Locks the lock for reading without waiting like `RwLock::try_read`, holding on to the lock
+    /// through an `Arc` so that the guard is `'static`
+    ///
+    /// # Example
+    /// ```rs
+    /// if let Some(value) = lock.clone().try_read_owned() {
+    ///     println!("{}", *value);
+    /// }
+    /// ```
+    pub fn try_read_owned(self: Arc<Self>) -> Option<OwnedRwLockReadGuard<T>> {
+        self.semaphore.try_acquire(1).ok()?.forget();
+        Some(OwnedRwLockReadGuard {
+            value: self.value.get(),
+            lock: self,
+        })
+    }
+
+    /// Waits for the lock to be unlocked and locks it for writing, returning a guard that unlocks
+    /// it when dropped
+    ///
+    /// # Example
+    /// ```rs
+    /// let mut value = lock.write().await;
+    /// *value += 1;
+    /// ```
+    pub async fn write(&self) -> RwLockWriteGuard<'_, T> {
+        acquired(self.semaphore.acquire(MAX_READS).await).forget();
+        RwLockWriteGuard {
+            lock: self,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Locks the lock for writing like `RwLock::write`, holding on to the lock through an `Arc` so
+    /// that the guard is `'static`
+    ///
+    /// # Example
+    /// ```rs
+    /// let mut value = lock.clone().write_owned().await;
+    /// ```
+    pub async fn write_owned(self: Arc<Self>) -> OwnedRwLockWriteGuard<T> {
+        acquired(self.semaphore.acquire(MAX_READS).await).forget();
+        OwnedRwLockWriteGuard { lock: self }
+    }
+
+    /// Locks the lock for writing without waiting, returning `None` if it's locked or other
+    /// futures are already waiting to lock it
+    ///
+    /// # Example
+    /// ```rs
+    /// if let Some(mut value) = lock.try_write() {
+    ///     *value += 1;
+    /// }
+    /// ```
+    pub fn try_write(&self) -> Option<RwLockWriteGuard<'_, T>> {
+        self.semaphore.try_acquire(MAX_READS).ok()?.forget();
+        Some(RwLockWriteGuard {
+            lock: self,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Locks the lock for writing without waiting like `RwLock::try_write`, holding on to the lock
+    /// through an `Arc` so that the guard is `'static`
+    ///
+    /// # Example
+    /// ```rs
+    /// if let Some(mut value) = lock.clone().try_write_owned() {
+    ///     *value += 1;
+    /// }
+    /// ```
+    pub fn try_write_owned(self: Arc<Self>) -> Option<OwnedRwLockWriteGuard<T>> {
+        self.semaphore.try_acquire(MAX_READS).ok()?.forget();
+        Some(OwnedRwLockWriteGuard { lock: self })
+    }
+
+    /// Returns a mutable reference to the value, which needs no locking since the lock is
+    /// borrowed mutably
+    ///
+    /// # Example
+    /// ```rs
+    /// *lock.get_mut() += 1;
+    /// ```
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+}
+
+impl<T: Default> Default for RwLock<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T> From<T> for RwLock<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for RwLock<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.try_read() {
+            Some(value) => f.debug_struct("RwLock").field("value", &&*value).finish(),
+            None => f.write_str("RwLock { <locked> }"),
+        }
+    }
+}
+
+/// A guard that holds an `RwLock` locked for reading until it's dropped
+#[must_use = "the lock is unlocked as soon as the guard is dropped"]
+pub struct RwLockReadGuard<'a, T: ?Sized> {
+    semaphore: &'a Semaphore,
+    value: &'a T,
+}
+
+impl<'a, T: ?Sized> RwLockReadGuard<'a, T> {
+    /// Makes a guard for a part of the locked value, which keeps the lock locked for reading until
+    /// it's dropped
+    ///
+    /// # Example
+    /// ```rs
+    /// let name = RwLockReadGuard::map(lock.read().await, |user| &user.name);
+    /// ```
+    pub fn map<U: ?Sized>(this: Self, f: impl FnOnce(&T) -> &U) -> RwLockReadGuard<'a, U> {
+        let semaphore = this.semaphore;
+        let value = f(this.value);
+        // only forgotten once `f` has returned, so the lock is still unlocked if it panics
+        mem::forget(this);
+        RwLockReadGuard { semaphore, value }
+    }
+}
+
+impl<T: ?Sized> Deref for RwLockReadGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.value
+    }
+}
+
+impl<T: ?Sized> Drop for RwLockReadGuard<'_, T> {
+    fn drop(&mut self) {
+        self.semaphore.add_permits(1);
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for RwLockReadGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+/// A guard that holds an `RwLock` in an `Arc` locked for reading until it's dropped
+#[must_use = "the lock is unlocked as soon as the guard is dropped"]
+pub struct OwnedRwLockReadGuard<T: ?Sized, U: ?Sized = T> {
+    lock: Arc<RwLock<T>>,
+    value: *const U,
+}
+
+// SAFETY: the guard gives shared access to a part of the value, as if it was a `&U`
+unsafe impl<T: ?Sized + Send + Sync, U: ?Sized + Sync> Send for OwnedRwLockReadGuard<T, U> {}
+unsafe impl<T: ?Sized + Send + Sync, U: ?Sized + Sync> Sync for OwnedRwLockReadGuard<T, U> {}
+
+impl<T: ?Sized, U: ?Sized> OwnedRwLockReadGuard<T, U> {
+    /// Makes a guard for a part of the locked value, which keeps the lock locked for reading until
+    /// it's dropped
+    ///
+    /// # Example
+    /// ```rs
+    /// let name = OwnedRwLockReadGuard::map(lock.read_owned().await, |user| &user.name);
+    /// ```
+    pub fn map<V: ?Sized>(this: Self, f: impl FnOnce(&U) -> &V) -> OwnedRwLockReadGuard<T, V> {
+        // SAFETY: holding a read permit means nothing is writing the value
+        let value = f(unsafe { &*this.value }) as *const V;
+        // only forgotten once `f` has returned, so the lock is still unlocked if it panics
+        let this = ManuallyDrop::new(this);
+        // SAFETY: the guard is never dropped, so moving the lock out of it leaves the lock locked
+        // for the new guard
+        let lock = unsafe { core::ptr::read(&this.lock) };
+        OwnedRwLockReadGuard { lock, value }
+    }
+}
+
+impl<T: ?Sized, U: ?Sized> Deref for OwnedRwLockReadGuard<T, U> {
+    type Target = U;
+
+    fn deref(&self) -> &U {
+        // SAFETY: holding a read permit means nothing is writing the value
+        unsafe { &*self.value }
+    }
+}
+
+impl<T: ?Sized, U: ?Sized> Drop for OwnedRwLockReadGuard<T, U> {
+    fn drop(&mut self) {
+        self.lock.semaphore.add_permits(1);
+    }
+}
+
+impl<T: ?Sized, U: ?Sized + fmt::Debug> fmt::Debug for OwnedRwLockReadGuard<T, U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+/// A guard that holds an `RwLock` locked for writing until it's dropped
+#[must_use = "the lock is unlocked as soon as the guard is dropped"]
+pub struct RwLockWriteGuard<'a, T: ?Sized> {
+    lock: &'a RwLock<T>,
+    // sharing the guard shares `&T` and sending it sends `&mut T`
+    _marker: PhantomData<&'a mut T>,
+}
+
+impl<'a, T: ?Sized> RwLockWriteGuard<'a, T> {
+    /// Makes a guard for a part of the locked value, which keeps the lock locked for writing until
+    /// it's dropped
+    ///
+    /// # Example
+    /// ```rs
+    /// let name = RwLockWriteGuard::map(lock.write().await, |user| &mut user.name);
+    /// ```
+    pub fn map<U: ?Sized>(
+        this: Self,
+        f: impl FnOnce(&mut T) -> &mut U,
+    ) -> MappedRwLockWriteGuard<'a, U> {
+        let lock = this.lock;
+        // SAFETY: the guard gives exclusive access to the value until the mapped guard unlocks the
+        // lock
+        let value = f(unsafe { &mut *lock.value.get() });
+        // only forgotten once `f` has returned, so the lock is still unlocked if it panics
+        mem::forget(this);
+        MappedRwLockWriteGuard {
+            semaphore: &lock.semaphore,
+            value,
+        }
+    }
+}
+
+impl<T: ?Sized> Deref for RwLockWriteGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: holding every permit gives exclusive access to the value
+        unsafe { &*self.lock.value.get() }
+    }
+}
+
+impl<T: ?Sized> DerefMut for RwLockWriteGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        // SAFETY: holding every permit gives exclusive access to the value
+        unsafe { &mut *self.lock.value.get() }
+    }
+}
+
+impl<T: ?Sized> Drop for RwLockWriteGuard<'_, T> {
+    fn drop(&mut self) {
+        self.lock.semaphore.add_permits(MAX_READS);
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for RwLockWriteGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+/// A guard that holds an `RwLock` in an `Arc` locked for writing until it's dropped
+#[must_use = "the lock is unlocked as soon as the guard is dropped"]
+pub struct OwnedRwLockWriteGuard<T: ?Sized> {
+    lock: Arc<RwLock<T>>,
+}
+
+// SAFETY: sharing the guard shares `&T`, so it's only `Sync` if `T` is
+unsafe impl<T: ?Sized + Send + Sync> Sync for OwnedRwLockWriteGuard<T> {}
+
+impl<T: ?Sized> OwnedRwLockWriteGuard<T> {
+    /// Makes a guard for a part of the locked value, which keeps the lock locked for writing until
+    /// it's dropped
+    ///
+    /// # Example
+    /// ```rs
+    /// let name = OwnedRwLockWriteGuard::map(lock.write_owned().await, |user| &mut user.name);
+    /// ```
+    pub fn map<U: ?Sized>(
+        this: Self,
+        f: impl FnOnce(&mut T) -> &mut U,
+    ) -> OwnedMappedRwLockWriteGuard<T, U> {
+        // SAFETY: the guard gives exclusive access to the value until the mapped guard unlocks the
+        // lock
+        let value = f(unsafe { &mut *this.lock.value.get() }) as *mut U;
+        // only forgotten once `f` has returned, so the lock is still unlocked if it panics
+        let this = ManuallyDrop::new(this);
+        // SAFETY: the guard is never dropped, so moving the lock out of it leaves the lock locked
+        // for the mapped guard
+        let lock = unsafe { core::ptr::read(&this.lock) };
+        OwnedMappedRwLockWriteGuard { lock, value }
+    }
+}
+
+impl<T: ?Sized> Deref for OwnedRwLockWriteGuard<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: holding every permit gives exclusive access to the value
+        unsafe { &*self.lock.value.get() }
+    }
+}
+
+impl<T: ?Sized> DerefMut for OwnedRwLockWriteGuard<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        // SAFETY: holding every permit gives exclusive access to the value
+        unsafe { &mut *self.lock.value.get() }
+    }
+}
+
+impl<T: ?Sized> Drop for OwnedRwLockWriteGuard<T> {
+    fn drop(&mut self) {
+        self.lock.semaphore.add_permits(MAX_READS);
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for OwnedRwLockWriteGuard<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+/// A guard for a part of the value held by an `RwLock`, made with `RwLockWriteGuard::map`
+#[must_use = "the lock is unlocked as soon as the guard is dropped"]
+pub struct MappedRwLockWriteGuard<'a, U: ?Sized> {
+    semaphore: &'a Semaphore,
+    value: &'a mut U,
+}
+
+impl<'a, U: ?Sized> MappedRwLockWriteGuard<'a, U> {
+    /// Makes a guard for a part of the mapped value, which keeps the lock locked for writing until
+    /// it's dropped
+    ///
+    /// # Example
+    /// ```rs
+    /// let first = MappedRwLockWriteGuard::map(names, |names| &mut names[0]);
+    /// ```
+    pub fn map<V: ?Sized>(
+        this: Self,
+        f: impl FnOnce(&mut U) -> &mut V,
+    ) -> MappedRwLockWriteGuard<'a, V> {
+        let semaphore = this.semaphore;
+        // SAFETY: the guard is forgotten once `f` returns, leaving the new guard as the only way to
+        // reach the value
+        let value = f(unsafe { &mut *(&mut *this.value as *mut U) });
+        // only forgotten once `f` has returned, so the lock is still unlocked if it panics
+        mem::forget(this);
+        MappedRwLockWriteGuard { semaphore, value }
+    }
+}
+
+impl<U: ?Sized> Deref for MappedRwLockWriteGuard<'_, U> {
+    type Target = U;
+
+    fn deref(&self) -> &U {
+        self.value
+    }
+}
+
+impl<U: ?Sized> DerefMut for MappedRwLockWriteGuard<'_, U> {
+    fn deref_mut(&mut self) -> &mut U {
+        self.value
+    }
+}
+
+impl<U: ?Sized> Drop for MappedRwLockWriteGuard<'_, U> {
+    fn drop(&mut self) {
+        self.semaphore.add_permits(MAX_READS);
+    }
+}
+
+impl<U: ?Sized + fmt::Debug> fmt::Debug for MappedRwLockWriteGuard<'_, U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+/// A guard for a part of the value held by an `RwLock` in an `Arc`, made with
+/// `OwnedRwLockWriteGuard::map`
+#[must_use = "the lock is unlocked as soon as the guard is dropped"]
+pub struct OwnedMappedRwLockWriteGuard<T: ?Sized, U: ?Sized> {
+    lock: Arc<RwLock<T>>,
+    value: *mut U,
+}
+
+// SAFETY: the guard gives exclusive access to a part of the value, as if it was a `&mut U`
+unsafe impl<T: ?Sized + Send + Sync, U: ?Sized + Send> Send for OwnedMappedRwLockWriteGuard<T, U> {}
+unsafe impl<T: ?Sized + Send + Sync, U: ?Sized + Sync> Sync for OwnedMappedRwLockWriteGuard<T, U> {}
+
+impl<T: ?Sized, U: ?Sized> OwnedMappedRwLockWriteGuard<T, U> {
+    /// Makes a guard for a part of the mapped value, which keeps the lock locked for writing until
+    /// it's dropped
+    ///
+    /// # Example
+    /// ```rs
+    /// let first = OwnedMappedRwLockWriteGuard::map(names, |names| &mut names[0]);
+    /// ```
+    pub fn map<V: ?Sized>(
+        this: Self,
+        f: impl FnOnce(&mut U) -> &mut V,
+    ) -> OwnedMappedRwLockWriteGuard<T, V> {
+        // SAFETY: the guard gives exclusive access to the value until the new guard unlocks the
+        // lock
+        let value = f(unsafe { &mut *this.value }) as *mut V;
+        // only forgotten once `f` has returned, so the lock is still unlocked if it panics
+        let this = ManuallyDrop::new(this);
+        // SAFETY: the guard is never dropped, so moving the lock out of it leaves the lock locked
+        // for the new guard
+        let lock = unsafe { core::ptr::read(&this.lock) };
+        OwnedMappedRwLockWriteGuard { lock, value }
+    }
+}
+
+impl<T: ?Sized, U: ?Sized> Deref for OwnedMappedRwLockWriteGuard<T, U> {
+    type Target = U;
+
+    fn deref(&self) -> &U {
+        // SAFETY: holding every permit gives exclusive access to the value
+        unsafe { &*self.value }
+    }
+}
+
+impl<T: ?Sized, U: ?Sized> DerefMut for OwnedMappedRwLockWriteGuard<T, U> {
+    fn deref_mut(&mut self) -> &mut U {
+        // SAFETY: holding every permit gives exclusive access to the value
+        unsafe { &mut *self.value }
+    }
+}
+
+impl<T: ?Sized, U: ?Sized> Drop for OwnedMappedRwLockWriteGuard<T, U> {
+    fn drop(&mut self) {
+        self.lock.semaphore.add_permits(MAX_READS);
+    }
+}
+
+impl<T: ?Sized, U: ?Sized + fmt::Debug> fmt::Debug for OwnedMappedRwLockWriteGuard<T, U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
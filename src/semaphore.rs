@@ -0,0 +1,377 @@
+use alloc::sync::Arc;
+use core::task::{Context, Poll};
+
+use crate::{
+    list::{Cancelled, Registered, Registration, WaitList, WaitState, Wakeups},
+    lock::Lock,
+    Closed, TryAcquireError,
+};
+
+/// The Semaphore primitive limits how many futures can do something at once, by handing out a
+/// fixed number of permits that are returned when they're dropped.
+///
+/// # Example
+///
+/// ```rs
+/// use casus::Semaphore;
+///
+/// let semaphore = Semaphore::new(4);
+///
+/// // this will block until a permit is available
+/// let permit = semaphore.acquire(1).await?;
+/// ```
+///
+/// Acquires are served in the order they started waiting, so an acquire waiting for many permits
+/// holds up the ones behind it instead of being starved by them.
+
+#[derive(Debug)]
+pub struct Semaphore {
+    state: Lock<State>,
+}
+
+#[derive(Debug)]
+struct State {
+    permits: usize,
+    closed: bool,
+    // each waiter carries the number of permits it's waiting for, and is woken with `Ok` holding
+    // that number once they have been handed to it or `Err` if the semaphore is closed first
+    waiters: WaitList<Result<usize, Closed>, usize>,
+}
+
+impl State {
+    /// Hands permits to the waiters at the front of the queue for as long as there are enough
+    fn grant(&mut self, wakeups: &mut Wakeups<Result<usize, Closed>>) {
+        while let Some(&n) = self.waiters.front() {
+            if n > self.permits {
+                break;
+            }
+            self.permits -= n;
+            if !self.waiters.wake_front(Ok(n), wakeups) {
+                self.permits += n;
+            }
+        }
+    }
+}
+
+impl Semaphore {
+    /// Creates a new `Semaphore` with `permits` permits available
+    ///
+    /// # Example
+    /// ```rs
+    /// use casus::Semaphore;
+    ///
+    /// let semaphore = Semaphore::new(4);
+    /// ```
+    pub fn new(permits: usize) -> Self {
+        Self {
+            state: Lock::new(State {
+                permits,
+                closed: false,
+                waiters: WaitList::new(),
+            }),
+        }
+    }
+
+    /// Waits for `n` permits to be available and acquires them, or fails with `Closed` if the
+    /// semaphore is closed first. The permits are returned when the `SemaphorePermit` is dropped.
+    ///
+    /// # Example
+    /// ```rs
+    /// let permit = semaphore.acquire(2).await?;
+    /// // two permits are held until `permit` is dropped
+    /// ```
+    pub async fn acquire(&self, n: usize) -> Result<SemaphorePermit<'_>, Closed> {
+        self.acquire_permits(n).await?;
+        Ok(SemaphorePermit {
+            semaphore: self,
+            permits: n,
+        })
+    }
+
+    /// Waits for `n` permits like `Semaphore::acquire`, holding on to the semaphore through an
+    /// `Arc` so that the permit is `'static`
+    ///
+    /// # Example
+    /// ```rs
+    /// let permit = semaphore.clone().acquire_owned(1).await?;
+    /// tokio::spawn(async move {
+    ///     // the permit is held until the task completes
+    ///     drop(permit);
+    /// });
+    /// ```
+    pub async fn acquire_owned(self: Arc<Self>, n: usize) -> Result<OwnedSemaphorePermit, Closed> {
+        self.acquire_permits(n).await?;
+        Ok(OwnedSemaphorePermit {
+            semaphore: self,
+            permits: n,
+        })
+    }
+
+    /// Acquires `n` permits without waiting, failing with `TryAcquireError::NoPermits` if there
+    /// aren't enough available or other acquires are already waiting
+    ///
+    /// # Example
+    /// ```rs
+    /// match semaphore.try_acquire(1) {
+    ///     Ok(permit) => println!("acquired a permit"),
+    ///     Err(TryAcquireError::NoPermits) => println!("too busy"),
+    ///     Err(TryAcquireError::Closed) => println!("closed"),
+    /// }
+    /// ```
+    pub fn try_acquire(&self, n: usize) -> Result<SemaphorePermit<'_>, TryAcquireError> {
+        self.try_acquire_permits(n)?;
+        Ok(SemaphorePermit {
+            semaphore: self,
+            permits: n,
+        })
+    }
+
+    /// Acquires `n` permits without waiting like `Semaphore::try_acquire`, holding on to the
+    /// semaphore through an `Arc` so that the permit is `'static`
+    ///
+    /// # Example
+    /// ```rs
+    /// let permit = semaphore.clone().try_acquire_owned(1)?;
+    /// ```
+    pub fn try_acquire_owned(
+        self: Arc<Self>,
+        n: usize,
+    ) -> Result<OwnedSemaphorePermit, TryAcquireError> {
+        self.try_acquire_permits(n)?;
+        Ok(OwnedSemaphorePermit {
+            semaphore: self,
+            permits: n,
+        })
+    }
+
+    /// Adds `n` permits to the semaphore, handing them to waiting acquires
+    ///
+    /// # Example
+    /// ```rs
+    /// semaphore.add_permits(2);
+    /// ```
+    pub fn add_permits(&self, n: usize) {
+        let mut wakeups = Wakeups::new();
+        let mut state = self.state.lock();
+        state.permits += n;
+        state.grant(&mut wakeups);
+    }
+
+    /// Returns the number of permits that are available to acquire
+    ///
+    /// # Example
+    /// ```rs
+    /// println!("{} permits available", semaphore.available_permits());
+    /// ```
+    pub fn available_permits(&self) -> usize {
+        self.state.lock().permits
+    }
+
+    /// Closes the semaphore, failing every current and future acquire with `Closed`. Permits that
+    /// are already held stay valid.
+    ///
+    /// # Example
+    /// ```rs
+    /// semaphore.close();
+    /// assert!(semaphore.acquire(1).await.is_err());
+    /// ```
+    pub fn close(&self) {
+        let mut wakeups = Wakeups::new();
+        let mut state = self.state.lock();
+        state.closed = true;
+        state.waiters.wake_all(Err(Closed), &mut wakeups);
+    }
+
+    /// Checks if the semaphore has been closed
+    ///
+    /// # Example
+    /// ```rs
+    /// if semaphore.is_closed() {
+    ///     return;
+    /// }
+    /// ```
+    pub fn is_closed(&self) -> bool {
+        self.state.lock().closed
+    }
+
+    /// Waits for `n` permits and takes them from the semaphore, leaving the caller to return them
+    async fn acquire_permits(&self, n: usize) -> Result<(), Closed> {
+        let mut registration = {
+            let mut state = self.state.lock();
+            if state.closed {
+                return Err(Closed);
+            }
+            // only skip the queue if nothing is already waiting, so acquires stay in order
+            if state.waiters.front().is_none() && state.permits >= n {
+                state.permits -= n;
+                return Ok(());
+            }
+            Registration::new(&self.state, &mut state, n)
+        };
+        registration.wait().await.map(|_| ())
+    }
+
+    /// Polls for `n` permits like `Semaphore::acquire_permits`, keeping the acquire in `pending`
+    /// between polls instead of in a future. An acquire that is given up on while pending has to
+    /// be passed to `Semaphore::cancel_acquire`.
+    pub(crate) fn poll_acquire_permits(
+        &self,
+        n: usize,
+        pending: &mut Option<PendingAcquire>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Closed>> {
+        if pending.is_none() {
+            let mut state = self.state.lock();
+            if state.closed {
+                return Poll::Ready(Err(Closed));
+            }
+            if state.waiters.front().is_none() && state.permits >= n {
+                state.permits -= n;
+                return Poll::Ready(Ok(()));
+            }
+            *pending = Some(PendingAcquire(Registered::new(&mut state, n)));
+        }
+        let Some(PendingAcquire(registered)) = pending else {
+            unreachable!("a pending acquire was just registered")
+        };
+        let result = match registered.poll(cx) {
+            Poll::Ready(result) => result,
+            Poll::Pending => return Poll::Pending,
+        };
+        *pending = None;
+        Poll::Ready(result.map(|_| ()))
+    }
+
+    /// Gives up on an acquire started by `Semaphore::poll_acquire_permits`
+    pub(crate) fn cancel_acquire(&self, pending: PendingAcquire) {
+        let mut wakeups = Wakeups::new();
+        pending.0.cancel(&mut self.state.lock(), &mut wakeups);
+    }
+
+    /// Takes `n` permits from the semaphore without waiting, leaving the caller to return them
+    fn try_acquire_permits(&self, n: usize) -> Result<(), TryAcquireError> {
+        let mut state = self.state.lock();
+        if state.closed {
+            return Err(TryAcquireError::Closed);
+        }
+        if state.waiters.front().is_some() || state.permits < n {
+            return Err(TryAcquireError::NoPermits);
+        }
+        state.permits -= n;
+        Ok(())
+    }
+}
+
+/// An acquire started by `Semaphore::poll_acquire_permits` that is still waiting for its permits
+#[derive(Debug)]
+pub(crate) struct PendingAcquire(Registered<State>);
+
+/// Permits acquired from a `Semaphore`, which are returned to it when dropped
+#[derive(Debug)]
+#[must_use = "the permits are released as soon as they're dropped"]
+pub struct SemaphorePermit<'a> {
+    semaphore: &'a Semaphore,
+    permits: usize,
+}
+
+impl SemaphorePermit<'_> {
+    /// Returns the number of permits held
+    ///
+    /// # Example
+    /// ```rs
+    /// assert_eq!(semaphore.acquire(2).await?.permits(), 2);
+    /// ```
+    pub fn permits(&self) -> usize {
+        self.permits
+    }
+
+    /// Drops the permits without returning them to the semaphore, permanently reducing the number
+    /// of permits it has
+    ///
+    /// # Example
+    /// ```rs
+    /// semaphore.acquire(1).await?.forget();
+    /// ```
+    pub fn forget(mut self) {
+        self.permits = 0;
+    }
+}
+
+impl Drop for SemaphorePermit<'_> {
+    fn drop(&mut self) {
+        self.semaphore.add_permits(self.permits);
+    }
+}
+
+/// Permits acquired from a `Semaphore` held in an `Arc`, which are returned to it when dropped
+#[derive(Debug)]
+#[must_use = "the permits are released as soon as they're dropped"]
+pub struct OwnedSemaphorePermit {
+    semaphore: Arc<Semaphore>,
+    permits: usize,
+}
+
+impl OwnedSemaphorePermit {
+    /// Returns the number of permits held
+    ///
+    /// # Example
+    /// ```rs
+    /// assert_eq!(semaphore.clone().acquire_owned(2).await?.permits(), 2);
+    /// ```
+    pub fn permits(&self) -> usize {
+        self.permits
+    }
+
+    /// Returns the semaphore the permits were acquired from
+    ///
+    /// # Example
+    /// ```rs
+    /// let semaphore = permit.semaphore().clone();
+    /// ```
+    pub fn semaphore(&self) -> &Arc<Semaphore> {
+        &self.semaphore
+    }
+
+    /// Drops the permits without returning them to the semaphore, permanently reducing the number
+    /// of permits it has
+    ///
+    /// # Example
+    /// ```rs
+    /// semaphore.clone().acquire_owned(1).await?.forget();
+    /// ```
+    pub fn forget(mut self) {
+        self.permits = 0;
+    }
+}
+
+impl Drop for OwnedSemaphorePermit {
+    fn drop(&mut self) {
+        self.semaphore.add_permits(self.permits);
+    }
+}
+
+impl WaitState for State {
+    type Value = Result<usize, Closed>;
+    type Data = usize;
+
+    fn waiters(&mut self) -> &mut WaitList<Result<usize, Closed>, usize> {
+        &mut self.waiters
+    }
+
+    fn cancelled(
+        &mut self,
+        cancelled: Cancelled<Result<usize, Closed>, usize>,
+        wakeups: &mut Wakeups<Result<usize, Closed>>,
+    ) {
+        match cancelled {
+            // the waiters behind this one may have been waiting for it to be served first
+            Cancelled::Registered(_) => self.grant(wakeups),
+            // the permits were handed to this waiter but it was dropped before it could return
+            Cancelled::Woken(Ok(n)) => {
+                self.permits += n;
+                self.grant(wakeups);
+            },
+            Cancelled::Woken(Err(Closed)) => {},
+        }
+    }
+}
@@ -0,0 +1,149 @@
+//! A single background thread that wakes futures once their deadline has passed, so that timeouts
+//! work the same under any runtime
+
+use std::{
+    collections::BTreeMap,
+    future::{poll_fn, Future},
+    pin::{pin, Pin},
+    sync::{Condvar, Mutex, OnceLock},
+    task::{Context, Poll, Waker},
+    thread,
+    time::Instant,
+};
+
+use crate::TimedOut;
+
+struct Timer {
+    entries: Mutex<Entries>,
+    condvar: Condvar,
+}
+
+struct Entries {
+    next_id: u64,
+    // keyed by deadline first so the earliest deadline is always the first entry
+    wakers: BTreeMap<(Instant, u64), Waker>,
+}
+
+fn timer() -> &'static Timer {
+    static TIMER: OnceLock<Timer> = OnceLock::new();
+    TIMER.get_or_init(|| {
+        thread::Builder::new()
+            .name("casus-timer".into())
+            .spawn(run)
+            .expect("failed to spawn the casus timer thread");
+        Timer {
+            entries: Mutex::new(Entries {
+                next_id: 0,
+                wakers: BTreeMap::new(),
+            }),
+            condvar: Condvar::new(),
+        }
+    })
+}
+
+fn run() {
+    let timer = timer();
+    let mut expired = Vec::new();
+    let mut entries = timer.entries.lock().unwrap();
+    loop {
+        let now = Instant::now();
+        while let Some(entry) = entries.wakers.first_entry() {
+            if entry.key().0 > now {
+                break;
+            }
+            expired.push(entry.remove());
+        }
+        if !expired.is_empty() {
+            // wake outside of the lock in case a waker polls the future inline
+            drop(entries);
+            expired.drain(..).for_each(Waker::wake);
+            entries = timer.entries.lock().unwrap();
+            continue;
+        }
+        entries = match entries.wakers.keys().next() {
+            Some(&(deadline, _)) => {
+                timer
+                    .condvar
+                    .wait_timeout(entries, deadline - now)
+                    .unwrap()
+                    .0
+            },
+            None => timer.condvar.wait(entries).unwrap(),
+        };
+    }
+}
+
+/// A future that completes once its deadline has passed
+#[derive(Debug)]
+pub(crate) struct Sleep {
+    deadline: Instant,
+    key: Option<(Instant, u64)>,
+}
+
+impl Sleep {
+    pub(crate) fn new(deadline: Instant) -> Self {
+        Self {
+            deadline,
+            key: None,
+        }
+    }
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if Instant::now() >= self.deadline {
+            return Poll::Ready(());
+        }
+        let timer = timer();
+        let mut entries = timer.entries.lock().unwrap();
+        let key = match self.key {
+            Some(key) => key,
+            None => {
+                let key = (self.deadline, entries.next_id);
+                entries.next_id += 1;
+                if entries
+                    .wakers
+                    .keys()
+                    .next()
+                    .is_none_or(|first| key < *first)
+                {
+                    // the timer thread is sleeping until a later deadline, or indefinitely
+                    timer.condvar.notify_one();
+                }
+                self.key = Some(key);
+                key
+            },
+        };
+        entries.wakers.insert(key, cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+impl Drop for Sleep {
+    fn drop(&mut self) {
+        if let Some(key) = self.key {
+            timer().entries.lock().unwrap().wakers.remove(&key);
+        }
+    }
+}
+
+/// Runs `fut` until it completes or `deadline` passes, a deadline of `None` never passes
+pub(crate) async fn timeout<F: Future>(
+    fut: F,
+    deadline: Option<Instant>,
+) -> Result<F::Output, TimedOut> {
+    let mut fut = pin!(fut);
+    let mut sleep = deadline.map(Sleep::new);
+    poll_fn(|cx| {
+        if let Poll::Ready(v) = fut.as_mut().poll(cx) {
+            return Poll::Ready(Ok(v));
+        }
+        match &mut sleep {
+            Some(sleep) => Pin::new(sleep).poll(cx).map(|()| Err(TimedOut)),
+            None => Poll::Pending,
+        }
+    })
+    .await
+}
@@ -0,0 +1,242 @@
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "std")]
+use crate::{blocking, timer, TimedOut};
+use crate::{
+    list::{WaitList, Wakeups},
+    lock::Lock,
+    Closed, Waiter,
+};
+
+/// The ValueEvent primitive is an `Event` that is set with a value. Every future waiting on it is
+/// woken up with a clone of the value, and any future calls will immediately return a clone of it
+/// until the event is cleared.
+///
+/// # Example
+///
+/// ```rs
+/// use casus::ValueEvent;
+///
+/// let config = ValueEvent::new();
+///
+/// // this will block until ValueEvent::set is called elsewhere
+/// let snapshot = config.wait().await;
+/// ```
+
+#[derive(Debug)]
+pub struct ValueEvent<T> {
+    inner: Lock<Inner<T>>,
+}
+
+#[derive(Debug)]
+struct Inner<T> {
+    value: Option<T>,
+    waiters: WaitList<T>,
+}
+
+impl<T: Clone> ValueEvent<T> {
+    /// Creates a new, unset `ValueEvent`
+    ///
+    /// # Example
+    /// ```rs
+    /// use casus::ValueEvent;
+    ///
+    /// let event = ValueEvent::<String>::new();
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            inner: Lock::new(Inner {
+                value: None,
+                waiters: WaitList::new(),
+            }),
+        }
+    }
+
+    /// Waits for the event to be set, returning a clone of its value
+    ///
+    /// # Example
+    /// ```rs
+    /// // will return when `ValueEvent::set` is called
+    /// let value = event.wait().await;
+    /// ```
+    pub fn wait(&self) -> ValueEventWait<'_, T> {
+        ValueEventWait {
+            event: self,
+            registration: None,
+        }
+    }
+
+    /// Waits for the event to be set, giving up with `TimedOut` if it isn't set within `timeout`
+    ///
+    /// # Example
+    /// ```rs
+    /// match event.wait_timeout(Duration::from_secs(5)).await {
+    ///     Ok(v) => println!("set to {v}"),
+    ///     Err(TimedOut) => println!("not set within 5 seconds"),
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub async fn wait_timeout(&self, timeout: Duration) -> Result<T, TimedOut> {
+        timer::timeout(self.wait(), Instant::now().checked_add(timeout)).await
+    }
+
+    /// Waits for the event to be set, giving up with `TimedOut` if it isn't set by `deadline`
+    ///
+    /// # Example
+    /// ```rs
+    /// let deadline = Instant::now() + Duration::from_secs(5);
+    /// match event.wait_deadline(deadline).await {
+    ///     Ok(v) => println!("set to {v}"),
+    ///     Err(TimedOut) => println!("not set by the deadline"),
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub async fn wait_deadline(&self, deadline: Instant) -> Result<T, TimedOut> {
+        timer::timeout(self.wait(), Some(deadline)).await
+    }
+
+    /// Blocks the current thread until the event is set, returning a clone of its value
+    ///
+    /// # Example
+    /// ```rs
+    /// // will return when `ValueEvent::set` is called
+    /// let value = event.wait_blocking();
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn wait_blocking(&self) -> T {
+        blocking::block_on(self.wait())
+    }
+
+    /// Blocks the current thread until the event is set, giving up with `TimedOut` if it isn't set
+    /// within `timeout`
+    ///
+    /// # Example
+    /// ```rs
+    /// match event.wait_blocking_timeout(Duration::from_secs(5)) {
+    ///     Ok(v) => println!("set to {v}"),
+    ///     Err(TimedOut) => println!("not set within 5 seconds"),
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn wait_blocking_timeout(&self, timeout: Duration) -> Result<T, TimedOut> {
+        blocking::block_on_deadline(self.wait(), Instant::now().checked_add(timeout))
+    }
+
+    /// Blocks the current thread until the event is set, giving up with `TimedOut` if it isn't set
+    /// by `deadline`
+    ///
+    /// # Example
+    /// ```rs
+    /// let deadline = Instant::now() + Duration::from_secs(5);
+    /// match event.wait_blocking_deadline(deadline) {
+    ///     Ok(v) => println!("set to {v}"),
+    ///     Err(TimedOut) => println!("not set by the deadline"),
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn wait_blocking_deadline(&self, deadline: Instant) -> Result<T, TimedOut> {
+        blocking::block_on_deadline(self.wait(), Some(deadline))
+    }
+
+    /// Sets the event to `value`, waking every current waiter with a clone of it and returning the
+    /// value the event was previously set to, if any
+    ///
+    /// # Example
+    /// ```rs
+    /// event.set(String::from("value"));
+    /// ```
+    pub fn set(&self, value: T) -> Option<T> {
+        let mut wakeups = Wakeups::new();
+        let mut inner = self.inner.lock();
+        inner.waiters.wake_all(value.clone(), &mut wakeups);
+        inner.value.replace(value)
+    }
+
+    /// Clears the event, returning the value it was set to, if any. Later calls to
+    /// `ValueEvent::wait` will wait for the next `ValueEvent::set`.
+    ///
+    /// # Example
+    /// ```rs
+    /// let previous = event.clear();
+    /// ```
+    pub fn clear(&self) -> Option<T> {
+        self.inner.lock().value.take()
+    }
+
+    /// Returns a clone of the value the event is set to, without waiting
+    ///
+    /// # Example
+    /// ```rs
+    /// if let Some(value) = event.get() {
+    ///     println!("already set to {value}");
+    /// }
+    /// ```
+    pub fn get(&self) -> Option<T> {
+        self.inner.lock().value.clone()
+    }
+
+    /// Checks if the event is set
+    ///
+    /// # Example
+    /// ```rs
+    /// if !event.is_set() {
+    ///     event.wait().await;
+    /// }
+    /// ```
+    pub fn is_set(&self) -> bool {
+        self.inner.lock().value.is_some()
+    }
+}
+
+impl<T: Clone> Default for ValueEvent<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The future returned by `ValueEvent::wait`
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct ValueEventWait<'a, T> {
+    event: &'a ValueEvent<T>,
+    registration: Option<(u64, Waiter<T>)>,
+}
+
+impl<T: Clone> Future for ValueEventWait<'_, T> {
+    type Output = T;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        let this = &mut *self;
+        let (_, waiter) = match &mut this.registration {
+            Some(registration) => registration,
+            None => {
+                let mut inner = this.event.inner.lock();
+                if let Some(value) = &inner.value {
+                    return Poll::Ready(value.clone());
+                }
+                this.registration.insert(inner.waiters.register())
+            },
+        };
+        match Pin::new(waiter).poll(cx) {
+            Poll::Ready(Ok(value)) => Poll::Ready(value),
+            Poll::Ready(Err(Closed)) => {
+                unreachable!("value events hold on to a waiter's handle until they wake it")
+            },
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl<T> Drop for ValueEventWait<'_, T> {
+    fn drop(&mut self) {
+        if let Some((key, _)) = &self.registration {
+            self.event.inner.lock().waiters.remove(*key);
+        }
+    }
+}
@@ -0,0 +1,283 @@
+use alloc::sync::Arc;
+
+use crate::{
+    list::{Registration, WaitList, WaitState, Wakeups},
+    lock::Lock,
+};
+
+/// The WaitGroup primitive waits for a changing number of pieces of work to finish. Each piece of
+/// work holds a guard from `WaitGroup::add`, and waiting on the group completes once every guard
+/// has been dropped.
+///
+/// # Example
+///
+/// ```rs
+/// use casus::WaitGroup;
+///
+/// let group = Arc::new(WaitGroup::new());
+///
+/// for _ in 0..4 {
+///     let guard = group.clone().add_owned();
+///     tokio::spawn(async move {
+///         // the group waits for this task until the guard is dropped
+///         drop(guard);
+///     });
+/// }
+///
+/// // this will block until every task has dropped its guard
+/// group.wait().await;
+/// ```
+
+#[derive(Debug)]
+pub struct WaitGroup {
+    state: Lock<State>,
+}
+
+#[derive(Debug)]
+struct State {
+    count: usize,
+    waiters: WaitList<()>,
+}
+
+impl WaitGroup {
+    /// Creates a new `WaitGroup` with nothing to wait for
+    ///
+    /// # Example
+    /// ```rs
+    /// use casus::WaitGroup;
+    ///
+    /// let group = WaitGroup::new();
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            state: Lock::new(State {
+                count: 0,
+                waiters: WaitList::new(),
+            }),
+        }
+    }
+
+    /// Adds a piece of work to the group, which it waits for until the returned guard is dropped
+    ///
+    /// # Example
+    /// ```rs
+    /// let guard = group.add();
+    /// // the group waits for this work until `guard` is dropped
+    /// guard.done();
+    /// ```
+    pub fn add(&self) -> WaitGroupGuard<'_> {
+        self.state.lock().count += 1;
+        WaitGroupGuard { group: self }
+    }
+
+    /// Adds a piece of work to the group like `WaitGroup::add`, holding on to the group through an
+    /// `Arc` so that the guard is `'static`
+    ///
+    /// # Example
+    /// ```rs
+    /// let guard = group.clone().add_owned();
+    /// tokio::spawn(async move {
+    ///     drop(guard);
+    /// });
+    /// ```
+    pub fn add_owned(self: Arc<Self>) -> OwnedWaitGroupGuard {
+        self.state.lock().count += 1;
+        OwnedWaitGroupGuard { group: self }
+    }
+
+    /// Waits for every piece of work in the group to be done, completing straight away if there's
+    /// none
+    ///
+    /// # Example
+    /// ```rs
+    /// // will return when every guard has been dropped
+    /// group.wait().await;
+    /// ```
+    pub async fn wait(&self) {
+        let mut registration = {
+            let mut state = self.state.lock();
+            if state.count == 0 {
+                return;
+            }
+            Registration::new(&self.state, &mut state, ())
+        };
+        registration.wait().await;
+    }
+
+    /// Returns the number of pieces of work that aren't done yet
+    ///
+    /// # Example
+    /// ```rs
+    /// println!("{} tasks running", group.count());
+    /// ```
+    pub fn count(&self) -> usize {
+        self.state.lock().count
+    }
+
+    fn done(&self) {
+        let mut wakeups = Wakeups::new();
+        let mut state = self.state.lock();
+        state.count -= 1;
+        if state.count == 0 {
+            state.waiters.wake_all((), &mut wakeups);
+        }
+    }
+}
+
+impl Default for WaitGroup {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A piece of work added to a `WaitGroup`, which is done when the guard is dropped
+#[derive(Debug)]
+#[must_use = "the work is done as soon as the guard is dropped"]
+pub struct WaitGroupGuard<'a> {
+    group: &'a WaitGroup,
+}
+
+impl WaitGroupGuard<'_> {
+    /// Marks the work as done, which is the same as dropping the guard
+    ///
+    /// # Example
+    /// ```rs
+    /// group.add().done();
+    /// ```
+    pub fn done(self) {}
+}
+
+impl Drop for WaitGroupGuard<'_> {
+    fn drop(&mut self) {
+        self.group.done();
+    }
+}
+
+/// A piece of work added to a `WaitGroup` held in an `Arc`, which is done when the guard is dropped
+#[derive(Debug)]
+#[must_use = "the work is done as soon as the guard is dropped"]
+pub struct OwnedWaitGroupGuard {
+    group: Arc<WaitGroup>,
+}
+
+impl OwnedWaitGroupGuard {
+    /// Marks the work as done, which is the same as dropping the guard
+    ///
+    /// # Example
+    /// ```rs
+    /// group.clone().add_owned().done();
+    /// ```
+    pub fn done(self) {}
+
+    /// Returns the group the work was added to
+    ///
+    /// # Example
+    /// ```rs
+    /// let group = guard.group().clone();
+    /// ```
+    pub fn group(&self) -> &Arc<WaitGroup> {
+        &self.group
+    }
+}
+
+impl Drop for OwnedWaitGroupGuard {
+    fn drop(&mut self) {
+        self.group.done();
+    }
+}
+
+/// The CountdownLatch primitive waits for a fixed number of things to happen, each one counts the
+/// latch down and waiting on it completes once it reaches zero.
+///
+/// # Example
+///
+/// ```rs
+/// use casus::CountdownLatch;
+///
+/// let latch = CountdownLatch::new(3);
+///
+/// // this will block until `count_down` has been called 3 times
+/// latch.wait().await;
+/// ```
+///
+/// Unlike a `WaitGroup`, a latch can't count back up, so once it has reached zero it stays there.
+
+#[derive(Debug)]
+pub struct CountdownLatch {
+    state: Lock<State>,
+}
+
+impl CountdownLatch {
+    /// Creates a new `CountdownLatch` that completes its waiters after counting down `n` times
+    ///
+    /// # Example
+    /// ```rs
+    /// use casus::CountdownLatch;
+    ///
+    /// let latch = CountdownLatch::new(3);
+    /// ```
+    pub fn new(n: usize) -> Self {
+        Self {
+            state: Lock::new(State {
+                count: n,
+                waiters: WaitList::new(),
+            }),
+        }
+    }
+
+    /// Counts the latch down by one, waking every waiter if it reaches zero. Counting down a latch
+    /// that is already at zero does nothing.
+    ///
+    /// # Example
+    /// ```rs
+    /// latch.count_down();
+    /// ```
+    pub fn count_down(&self) {
+        let mut wakeups = Wakeups::new();
+        let mut state = self.state.lock();
+        if state.count == 0 {
+            return;
+        }
+        state.count -= 1;
+        if state.count == 0 {
+            state.waiters.wake_all((), &mut wakeups);
+        }
+    }
+
+    /// Waits for the latch to count down to zero
+    ///
+    /// # Example
+    /// ```rs
+    /// // will return when the latch reaches zero
+    /// latch.wait().await;
+    /// ```
+    pub async fn wait(&self) {
+        let mut registration = {
+            let mut state = self.state.lock();
+            if state.count == 0 {
+                return;
+            }
+            Registration::new(&self.state, &mut state, ())
+        };
+        registration.wait().await;
+    }
+
+    /// Returns how many more times the latch has to count down
+    ///
+    /// # Example
+    /// ```rs
+    /// println!("{} left", latch.count());
+    /// ```
+    pub fn count(&self) -> usize {
+        self.state.lock().count
+    }
+}
+
+impl WaitState for State {
+    type Value = ();
+    type Data = ();
+
+    fn waiters(&mut self) -> &mut WaitList<()> {
+        &mut self.waiters
+    }
+}
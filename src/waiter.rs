@@ -0,0 +1,554 @@
+use alloc::{sync::Arc, vec::Vec};
+use core::{
+    future::{poll_fn, Future},
+    pin::Pin,
+    sync::atomic::{AtomicUsize, Ordering},
+    task::{Context, Poll, Waker},
+};
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
+use crate::{atomic_waker::AtomicWaker, lock::Lock, Closed};
+#[cfg(feature = "std")]
+use crate::{blocking, timer, TimedOut};
+
+/// The Waiter primitive simply waits to be woken up with it's return value.
+///
+/// # Example
+///
+/// ```rs
+/// use casus::Waiter;
+///
+/// let (handle, waiter) = Waiter::new();
+///
+/// // this will block until WakeHandle::wake is called elsewhere
+/// waiter.await;
+/// ```
+///
+/// A waiter is woken up through the `WakeHandle` it was created with. If every clone of the handle
+/// is dropped without waking the waiter, awaiting it returns `Closed` instead of waiting forever.
+///
+/// If several clones of a `Waiter` are awaited at once they are all woken, but only the first to
+/// be polled afterwards gets the value and the others complete with `Closed`, use
+/// `Waiter::shared` if every clone should get a copy of it. Once a `Waiter` has completed it never
+/// completes again.
+
+#[derive(Debug)]
+pub struct Waiter<T> {
+    slot: Slot<T>,
+    done: bool,
+}
+
+impl<T> Waiter<T> {
+    /// Creates a new `Waiter`, along with the `WakeHandle` used to wake it up
+    ///
+    /// # Example
+    /// ```rs
+    /// use casus::Waiter;
+    ///
+    /// let (handle, waiter) = Waiter::new();
+    /// ```
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new() -> (WakeHandle<T>, Self) {
+        let (handle, slot) = Slot::new();
+        (handle, Self { slot, done: false })
+    }
+
+    /// Converts the waiter into a `SharedWaiter`, which gives a clone of the value to every clone
+    /// that awaits it
+    ///
+    /// # Example
+    /// ```rs
+    /// let waiter = waiter.shared();
+    /// ```
+    pub fn shared(self) -> SharedWaiter<T>
+    where
+        T: Clone,
+    {
+        SharedWaiter { slot: self.slot }
+    }
+
+    /// Takes the value the waiter was woken up with, if it has been woken up and the value hasn't
+    /// already been taken
+    pub(crate) fn try_take(&self) -> Option<T> {
+        self.slot.take()
+    }
+
+    /// Checks if every `WakeHandle` has been dropped, meaning the waiter can't be woken up anymore
+    pub(crate) fn is_closed(&self) -> bool {
+        self.slot.shared.state.load(Ordering::SeqCst) & HANDLES_CLOSED != 0
+    }
+
+    /// Waits to be woken up, giving up with `TimedOut` if `WakeHandle::wake` isn't called within
+    /// `timeout`
+    ///
+    /// # Example
+    /// ```rs
+    /// match waiter.wait_timeout(Duration::from_secs(5)).await {
+    ///     Ok(Ok(v)) => println!("woken with {v}"),
+    ///     Ok(Err(Closed)) => println!("every handle was dropped"),
+    ///     Err(TimedOut) => println!("not woken within 5 seconds"),
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub async fn wait_timeout(&mut self, timeout: Duration) -> Result<Result<T, Closed>, TimedOut> {
+        timer::timeout(self, Instant::now().checked_add(timeout)).await
+    }
+
+    /// Waits to be woken up, giving up with `TimedOut` if `WakeHandle::wake` isn't called by
+    /// `deadline`
+    ///
+    /// # Example
+    /// ```rs
+    /// let deadline = Instant::now() + Duration::from_secs(5);
+    /// match waiter.wait_deadline(deadline).await {
+    ///     Ok(Ok(v)) => println!("woken with {v}"),
+    ///     Ok(Err(Closed)) => println!("every handle was dropped"),
+    ///     Err(TimedOut) => println!("not woken by the deadline"),
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub async fn wait_deadline(
+        &mut self,
+        deadline: Instant,
+    ) -> Result<Result<T, Closed>, TimedOut> {
+        timer::timeout(self, Some(deadline)).await
+    }
+
+    /// Blocks the current thread until the waiter is woken up, returning the value it was woken
+    /// with or `Closed` if every `WakeHandle` was dropped first
+    ///
+    /// # Example
+    /// ```rs
+    /// // will return when `WakeHandle::wake` is called
+    /// let v = waiter.wait_blocking();
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn wait_blocking(&mut self) -> Result<T, Closed> {
+        blocking::block_on(self)
+    }
+
+    /// Blocks the current thread until the waiter is woken up, giving up with `TimedOut` if
+    /// `WakeHandle::wake` isn't called within `timeout`
+    ///
+    /// # Example
+    /// ```rs
+    /// match waiter.wait_blocking_timeout(Duration::from_secs(5)) {
+    ///     Ok(Ok(v)) => println!("woken with {v}"),
+    ///     Ok(Err(Closed)) => println!("every handle was dropped"),
+    ///     Err(TimedOut) => println!("not woken within 5 seconds"),
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn wait_blocking_timeout(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<Result<T, Closed>, TimedOut> {
+        blocking::block_on_deadline(self, Instant::now().checked_add(timeout))
+    }
+
+    /// Blocks the current thread until the waiter is woken up, giving up with `TimedOut` if
+    /// `WakeHandle::wake` isn't called by `deadline`
+    ///
+    /// # Example
+    /// ```rs
+    /// let deadline = Instant::now() + Duration::from_secs(5);
+    /// match waiter.wait_blocking_deadline(deadline) {
+    ///     Ok(Ok(v)) => println!("woken with {v}"),
+    ///     Ok(Err(Closed)) => println!("every handle was dropped"),
+    ///     Err(TimedOut) => println!("not woken by the deadline"),
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn wait_blocking_deadline(
+        &mut self,
+        deadline: Instant,
+    ) -> Result<Result<T, Closed>, TimedOut> {
+        blocking::block_on_deadline(self, Some(deadline))
+    }
+}
+
+impl<T> Clone for Waiter<T> {
+    fn clone(&self) -> Self {
+        Self {
+            slot: self.slot.clone(),
+            done: self.done,
+        }
+    }
+}
+
+impl<T> Future for Waiter<T> {
+    type Output = Result<T, Closed>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.done {
+            return Poll::Pending;
+        }
+        let poll = self.slot.poll(cx, Option::take);
+        self.done = poll.is_ready();
+        poll
+    }
+}
+
+/// A `Waiter` that gives a clone of the value it was woken up with to every clone that awaits it,
+/// including ones that start awaiting after it was woken up.
+///
+/// # Example
+///
+/// ```rs
+/// use casus::SharedWaiter;
+///
+/// let (handle, waiter) = SharedWaiter::new();
+/// let other = waiter.clone();
+///
+/// handle.wake(5);
+/// assert_eq!(waiter.await, Ok(5));
+/// assert_eq!(other.await, Ok(5));
+/// ```
+
+#[derive(Clone, Debug)]
+pub struct SharedWaiter<T> {
+    slot: Slot<T>,
+}
+
+impl<T: Clone> SharedWaiter<T> {
+    /// Creates a new `SharedWaiter`, along with the `WakeHandle` used to wake it up
+    ///
+    /// # Example
+    /// ```rs
+    /// use casus::SharedWaiter;
+    ///
+    /// let (handle, waiter) = SharedWaiter::new();
+    /// ```
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new() -> (WakeHandle<T>, Self) {
+        let (handle, slot) = Slot::new();
+        (handle, Self { slot })
+    }
+}
+
+impl<T: Clone> Future for SharedWaiter<T> {
+    type Output = Result<T, Closed>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.slot.poll(cx, |value| value.clone())
+    }
+}
+
+/// The sending half of a `Waiter` or `SharedWaiter`, used to wake it up with its return value.
+///
+/// # Example
+///
+/// ```rs
+/// use casus::Waiter;
+///
+/// let (handle, waiter) = Waiter::new();
+///
+/// handle.wake(5).unwrap();
+/// assert_eq!(waiter.await, Ok(5));
+/// ```
+
+#[derive(Debug)]
+pub struct WakeHandle<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> WakeHandle<T> {
+    /// Wakes up the waiter with `T` as the return value, meaning anything awaiting the waiter will
+    /// return the value T. A waiter is only woken once, so if it has already been woken through
+    /// any handle, or every clone of the waiter has been dropped, the value is handed back as
+    /// `Err(v)`.
+    ///
+    /// # Example
+    /// ```rs
+    /// if let Err(v) = handle.wake(T) {
+    ///     // nothing is left to receive v
+    /// }
+    /// ```
+    pub fn wake(&self, v: T) -> Result<(), T> {
+        self.deliver(v)?;
+        self.wake_waiters();
+        Ok(())
+    }
+
+    /// Checks if every clone of the waiter has been dropped, meaning waking it would fail
+    ///
+    /// # Example
+    /// ```rs
+    /// if !handle.is_closed() {
+    ///     handle.wake(T);
+    /// }
+    /// ```
+    pub fn is_closed(&self) -> bool {
+        self.shared.state.load(Ordering::SeqCst) & WAITERS_CLOSED != 0
+    }
+
+    /// Waits for every clone of the waiter to be dropped, which allows work whose result nobody
+    /// is waiting for anymore to be cancelled early
+    ///
+    /// # Example
+    /// ```rs
+    /// // will return when every clone of the waiter has been dropped
+    /// handle.closed().await;
+    /// ```
+    pub async fn closed(&self) {
+        if self.is_closed() {
+            return;
+        }
+        let registration = Registration::new(&self.shared.tx_wakers);
+        poll_fn(|cx| {
+            registration.waker.register(cx.waker());
+            if self.is_closed() {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        })
+        .await
+    }
+
+    /// Gives the waiter its value like `WakeHandle::wake` without waking it yet, so a caller
+    /// holding a lock can wake it with `WakeHandle::wake_waiters` once the lock is released
+    pub(crate) fn deliver(&self, v: T) -> Result<(), T> {
+        if self.is_closed() {
+            return Err(v);
+        }
+        let mut value = self.shared.value.lock();
+        // checked under the lock so that only the first wake delivers a value
+        if self.shared.state.load(Ordering::SeqCst) & (VALUE | TAKEN) != 0 {
+            return Err(v);
+        }
+        *value = Some(v);
+        self.shared.state.fetch_or(VALUE, Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// Wakes everything awaiting the waiter, so it can see the value it was delivered
+    pub(crate) fn wake_waiters(&self) {
+        self.shared.wake_waiters();
+    }
+}
+
+impl<T> Clone for WakeHandle<T> {
+    fn clone(&self) -> Self {
+        self.shared.handles.fetch_add(1, Ordering::Relaxed);
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> Drop for WakeHandle<T> {
+    fn drop(&mut self) {
+        if self.shared.handles.fetch_sub(1, Ordering::SeqCst) == 1 {
+            // the waiters can never be woken up now, so let them see that they're closed
+            let state = self.shared.state.fetch_or(HANDLES_CLOSED, Ordering::SeqCst);
+            if state & WAITERS_CLOSED == 0 {
+                self.shared.wake_waiters();
+            }
+        }
+    }
+}
+
+/// Set while there is a value that hasn't been taken yet
+const VALUE: usize = 0b001;
+/// Set once every `WakeHandle` has been dropped
+const HANDLES_CLOSED: usize = 0b010;
+/// Set once every clone of the waiter has been dropped
+const WAITERS_CLOSED: usize = 0b100;
+/// Set once a waiter has taken a value, so clones that lost the race complete with `Closed`
+const TAKEN: usize = 0b1000;
+
+#[derive(Debug)]
+struct Shared<T> {
+    // mirrors the value and the handle counts so that polls can check them without locking, it's
+    // always accessed with `SeqCst` since it pairs with `Wakers::len`
+    state: AtomicUsize,
+    handles: AtomicUsize,
+    receivers: AtomicUsize,
+    value: Lock<Option<T>>,
+    // the original waiter uses this slot, only its clones need to take a lock to get a slot of
+    // their own
+    rx_waker: AtomicWaker,
+    rx_wakers: Wakers,
+    tx_wakers: Wakers,
+}
+
+impl<T> Shared<T> {
+    fn wake_waiters(&self) {
+        if let Some(waker) = self.rx_waker.take() {
+            waker.wake();
+        }
+        self.rx_wakers.wake_all();
+    }
+}
+
+/// The waker slots of every future waiting on one side of a waiter. Each future owns its slot and
+/// registers into it without locking, the lock is only taken to add or remove slots and to wake
+/// them.
+#[derive(Debug)]
+struct Wakers {
+    // lets waking skip the lock when there's nothing to wake, which needs the state change before
+    // waking and the state check after inserting to be `SeqCst` so they can't both miss each other
+    len: AtomicUsize,
+    #[allow(clippy::type_complexity)]
+    list: Lock<(usize, Vec<(usize, Arc<AtomicWaker>)>)>,
+}
+
+impl Wakers {
+    fn new() -> Self {
+        Self {
+            len: AtomicUsize::new(0),
+            list: Lock::new((0, Vec::new())),
+        }
+    }
+
+    fn insert(&self, waker: Arc<AtomicWaker>) -> usize {
+        let mut list = self.list.lock();
+        let key = list.0;
+        list.0 += 1;
+        list.1.push((key, waker));
+        self.len.fetch_add(1, Ordering::SeqCst);
+        key
+    }
+
+    fn remove(&self, key: usize) {
+        let mut list = self.list.lock();
+        list.1.retain(|(k, _)| *k != key);
+        self.len.store(list.1.len(), Ordering::SeqCst);
+    }
+
+    fn wake_all(&self) {
+        if self.len.load(Ordering::SeqCst) == 0 {
+            return;
+        }
+        let wakers = self
+            .list
+            .lock()
+            .1
+            .iter()
+            .filter_map(|(_, waker)| waker.take())
+            .collect::<Vec<_>>();
+        // wake outside of the lock in case a waker polls the future inline
+        wakers.into_iter().for_each(Waker::wake);
+    }
+}
+
+/// A waker slot registered with a set of `Wakers`, which is removed again when dropped
+struct Registration<'a> {
+    wakers: &'a Wakers,
+    waker: Arc<AtomicWaker>,
+    key: usize,
+}
+
+impl<'a> Registration<'a> {
+    fn new(wakers: &'a Wakers) -> Self {
+        let waker = Arc::new(AtomicWaker::new());
+        let key = wakers.insert(waker.clone());
+        Self { wakers, waker, key }
+    }
+}
+
+impl Drop for Registration<'_> {
+    fn drop(&mut self) {
+        self.wakers.remove(self.key);
+    }
+}
+
+/// The state shared between clones of a waiter, along with the waker slot of clones other than
+/// the original
+#[derive(Debug)]
+struct Slot<T> {
+    shared: Arc<Shared<T>>,
+    waker: Option<(usize, Arc<AtomicWaker>)>,
+}
+
+impl<T> Slot<T> {
+    fn new() -> (WakeHandle<T>, Self) {
+        let shared = Arc::new(Shared {
+            state: AtomicUsize::new(0),
+            handles: AtomicUsize::new(1),
+            receivers: AtomicUsize::new(1),
+            value: Lock::new(None),
+            rx_waker: AtomicWaker::new(),
+            rx_wakers: Wakers::new(),
+            tx_wakers: Wakers::new(),
+        });
+        let handle = WakeHandle {
+            shared: shared.clone(),
+        };
+        (
+            handle,
+            Self {
+                shared,
+                waker: None,
+            },
+        )
+    }
+
+    /// Takes the value out of the shared state, if there is one
+    fn take(&self) -> Option<T> {
+        let mut value = self.shared.value.lock();
+        let v = value.take();
+        if v.is_some() {
+            self.shared.state.fetch_and(!VALUE, Ordering::SeqCst);
+            self.shared.state.fetch_or(TAKEN, Ordering::SeqCst);
+        }
+        v
+    }
+
+    /// Polls for the value, using `get` to take or clone it out of the shared state once woken
+    fn poll(
+        &mut self,
+        cx: &mut Context<'_>,
+        get: impl FnOnce(&mut Option<T>) -> Option<T>,
+    ) -> Poll<Result<T, Closed>> {
+        // registering before checking the state means a wake in between can't be missed
+        match &self.waker {
+            Some((_, waker)) => waker.register(cx.waker()),
+            None => self.shared.rx_waker.register(cx.waker()),
+        }
+        let state = self.shared.state.load(Ordering::SeqCst);
+        if state & VALUE != 0 {
+            let mut value = self.shared.value.lock();
+            if let Some(v) = get(&mut value) {
+                if value.is_none() {
+                    self.shared.state.fetch_and(!VALUE, Ordering::SeqCst);
+                    self.shared.state.fetch_or(TAKEN, Ordering::SeqCst);
+                }
+                return Poll::Ready(Ok(v));
+            }
+        }
+        // reloaded after looking for the value, a clone that took it set TAKEN under the lock
+        if self.shared.state.load(Ordering::SeqCst) & (HANDLES_CLOSED | TAKEN) != 0 {
+            return Poll::Ready(Err(Closed));
+        }
+        Poll::Pending
+    }
+}
+
+impl<T> Clone for Slot<T> {
+    fn clone(&self) -> Self {
+        self.shared.receivers.fetch_add(1, Ordering::Relaxed);
+        let waker = Arc::new(AtomicWaker::new());
+        let key = self.shared.rx_wakers.insert(waker.clone());
+        Self {
+            shared: self.shared.clone(),
+            waker: Some((key, waker)),
+        }
+    }
+}
+
+impl<T> Drop for Slot<T> {
+    fn drop(&mut self) {
+        if let Some((key, _)) = self.waker {
+            self.shared.rx_wakers.remove(key);
+        }
+        if self.shared.receivers.fetch_sub(1, Ordering::SeqCst) == 1 {
+            let state = self.shared.state.fetch_or(WAITERS_CLOSED, Ordering::SeqCst);
+            if state & HANDLES_CLOSED == 0 {
+                self.shared.tx_wakers.wake_all();
+            }
+        }
+    }
+}
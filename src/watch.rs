@@ -0,0 +1,251 @@
+use alloc::sync::Arc;
+use core::{fmt, ops::Deref};
+
+use crate::{
+    list::{Registration, WaitList, WaitState, Wakeups},
+    lock::Lock,
+};
+
+/// The Watch primitive holds a value that changes over time, letting futures wait for it to
+/// change or to reach a particular state.
+///
+/// # Example
+///
+/// ```rs
+/// use casus::Watch;
+///
+/// let mut watch = Watch::new(0);
+/// let sender = watch.clone();
+///
+/// // elsewhere
+/// sender.send(1);
+///
+/// // this will block until Watch::send is called with an even value
+/// watch.wait_until(|v| v % 2 == 0).await;
+/// ```
+///
+/// Every clone of a `Watch` can send and receive. Each one remembers the version of the value it
+/// last saw, so `Watch::changed` returns as soon as a newer value has been sent, even if it was
+/// sent before the call.
+
+#[derive(Debug)]
+pub struct Watch<T> {
+    shared: Arc<Shared<T>>,
+    version: u64,
+}
+
+#[derive(Debug)]
+struct Shared<T> {
+    state: Lock<State<T>>,
+    // held while sending so that a `Watch::send_modify` can't overwrite a value sent while it was
+    // modifying its copy, without holding the state lock while running user code
+    sending: Lock<()>,
+}
+
+#[derive(Debug)]
+struct State<T> {
+    // borrows are handed a clone of the `Arc`, so they never hold on to the lock
+    value: Arc<T>,
+    version: u64,
+    waiters: WaitList<()>,
+}
+
+impl<T> Watch<T> {
+    /// Creates a new `Watch` holding `value`
+    ///
+    /// # Example
+    /// ```rs
+    /// use casus::Watch;
+    ///
+    /// let watch = Watch::new(String::from("initial"));
+    /// ```
+    pub fn new(value: T) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                state: Lock::new(State {
+                    value: Arc::new(value),
+                    version: 0,
+                    waiters: WaitList::new(),
+                }),
+                sending: Lock::new(()),
+            }),
+            version: 0,
+        }
+    }
+
+    /// Replaces the value, waking every future waiting on it and returning the previous value
+    ///
+    /// # Example
+    /// ```rs
+    /// let previous = watch.send(2);
+    /// ```
+    pub fn send(&self, value: T) -> WatchRef<T> {
+        let _sending = self.shared.sending.lock();
+        self.publish(Arc::new(value))
+    }
+
+    /// Modifies a copy of the value and sends it, waking every future waiting on it. `f` must not
+    /// send to the watch itself.
+    ///
+    /// # Example
+    /// ```rs
+    /// watch.send_modify(|v| *v += 1);
+    /// ```
+    pub fn send_modify<R>(&self, f: impl FnOnce(&mut T) -> R) -> R
+    where
+        T: Clone,
+    {
+        let _sending = self.shared.sending.lock();
+        let mut value = T::clone(&self.shared.state.lock().value);
+        let result = f(&mut value);
+        self.publish(Arc::new(value));
+        result
+    }
+
+    /// Borrows the current value, without marking it as seen. The `WatchRef` keeps the value it
+    /// was borrowed with, so holding it doesn't stop the watch from being sent to.
+    ///
+    /// # Example
+    /// ```rs
+    /// println!("currently {}", *watch.borrow());
+    /// ```
+    pub fn borrow(&self) -> WatchRef<T> {
+        self.snapshot().1
+    }
+
+    /// Borrows the current value like `Watch::borrow`, marking it as seen
+    ///
+    /// # Example
+    /// ```rs
+    /// let value = watch.borrow_and_update().clone();
+    /// ```
+    pub fn borrow_and_update(&mut self) -> WatchRef<T> {
+        let (version, value) = self.snapshot();
+        self.version = version;
+        value
+    }
+
+    /// Checks if a value has been sent since this watch last saw one
+    ///
+    /// # Example
+    /// ```rs
+    /// if watch.has_changed() {
+    ///     let value = watch.borrow_and_update();
+    /// }
+    /// ```
+    pub fn has_changed(&self) -> bool {
+        self.shared.state.lock().version != self.version
+    }
+
+    /// Waits for a value newer than the last one this watch saw to be sent, marking it as seen
+    ///
+    /// # Example
+    /// ```rs
+    /// loop {
+    ///     let value = watch.changed().await;
+    ///     println!("changed to {}", *value);
+    /// }
+    /// ```
+    pub async fn changed(&mut self) -> WatchRef<T> {
+        let seen = self.version;
+        self.wait_for(|version, _| version != seen).await
+    }
+
+    /// Waits for the value to satisfy `predicate`, checking the current value first and then every
+    /// new value sent, and marks the value that satisfied it as seen
+    ///
+    /// # Example
+    /// ```rs
+    /// let ready = watch.wait_until(|state| state.is_ready()).await;
+    /// ```
+    pub async fn wait_until(&mut self, mut predicate: impl FnMut(&T) -> bool) -> WatchRef<T> {
+        self.wait_for(|_, value| predicate(value)).await
+    }
+
+    /// Waits for `done` to return true for the current version and value, marking that version as
+    /// seen
+    async fn wait_for(&mut self, mut done: impl FnMut(u64, &T) -> bool) -> WatchRef<T> {
+        loop {
+            // `done` runs on a snapshot, outside the lock
+            let (version, value) = self.snapshot();
+            if done(version, &value) {
+                self.version = version;
+                return value;
+            }
+            let mut registration = {
+                let mut state = self.shared.state.lock();
+                if state.version != version {
+                    // a value was sent while checking the snapshot, so check that one instead
+                    continue;
+                }
+                Registration::new(&self.shared.state, &mut state, ())
+            };
+            registration.wait().await;
+        }
+    }
+
+    /// Returns the current version along with a borrow of its value
+    fn snapshot(&self) -> (u64, WatchRef<T>) {
+        let state = self.shared.state.lock();
+        let value = WatchRef {
+            value: state.value.clone(),
+        };
+        (state.version, value)
+    }
+
+    /// Replaces the value with `value` and wakes every waiter, returning the previous value
+    fn publish(&self, value: Arc<T>) -> WatchRef<T> {
+        let mut wakeups = Wakeups::new();
+        let mut state = self.shared.state.lock();
+        let previous = core::mem::replace(&mut state.value, value);
+        state.version += 1;
+        state.waiters.wake_all((), &mut wakeups);
+        WatchRef { value: previous }
+    }
+}
+
+impl<T> Clone for Watch<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+            version: self.version,
+        }
+    }
+}
+
+impl<T: Default> Default for Watch<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+/// A borrow of a value held by a `Watch`, returned by `Watch::borrow` and the waits on a watch.
+/// It keeps the value it was borrowed with alive even after a newer one has been sent.
+
+#[derive(Clone)]
+pub struct WatchRef<T> {
+    value: Arc<T>,
+}
+
+impl<T> Deref for WatchRef<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for WatchRef<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T> WaitState for State<T> {
+    type Value = ();
+    type Data = ();
+
+    fn waiters(&mut self) -> &mut WaitList<()> {
+        &mut self.waiters
+    }
+}
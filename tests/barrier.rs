@@ -0,0 +1,64 @@
+mod common;
+
+use std::{pin::pin, sync::Arc, task::Poll, thread, time::Duration};
+
+use casus::Barrier;
+use common::{block_on_timeout, poll_once};
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+#[test]
+fn wait_releases_once_every_future_arrives() {
+    let barrier = Barrier::new(2);
+    let mut first = pin!(barrier.wait());
+    assert!(poll_once(first.as_mut()).is_pending());
+
+    let second = block_on_timeout(barrier.wait(), TIMEOUT);
+    assert!(second.is_leader());
+    assert_eq!(
+        poll_once(first.as_mut()).map(|r| r.is_leader()),
+        Poll::Ready(false)
+    );
+}
+
+#[test]
+fn wait_picks_one_leader_per_generation() {
+    let barrier = Arc::new(Barrier::new(4));
+    for _ in 0..3 {
+        let waiters = (0..4)
+            .map(|_| {
+                let barrier = barrier.clone();
+                thread::spawn(move || block_on_timeout(barrier.wait(), TIMEOUT).is_leader())
+            })
+            .collect::<Vec<_>>();
+        let leaders = waiters
+            .into_iter()
+            .map(|waiter| waiter.join().unwrap())
+            .filter(|&leader| leader)
+            .count();
+        assert_eq!(leaders, 1);
+    }
+}
+
+#[test]
+fn dropped_wait_does_not_count() {
+    let barrier = Barrier::new(2);
+    {
+        let mut wait = pin!(barrier.wait());
+        assert!(poll_once(wait.as_mut()).is_pending());
+    }
+
+    let mut wait = pin!(barrier.wait());
+    assert!(poll_once(wait.as_mut()).is_pending());
+    assert!(block_on_timeout(barrier.wait(), TIMEOUT).is_leader());
+    assert!(poll_once(wait.as_mut()).is_ready());
+}
+
+#[test]
+fn barrier_for_one_future_never_waits() {
+    for n in [0, 1] {
+        let barrier = Barrier::new(n);
+        assert!(block_on_timeout(barrier.wait(), TIMEOUT).is_leader());
+        assert!(block_on_timeout(barrier.wait(), TIMEOUT).is_leader());
+    }
+}
@@ -0,0 +1,99 @@
+mod common;
+
+use std::{
+    future::{pending, ready},
+    pin::pin,
+    task::Poll,
+    thread,
+    time::Duration,
+};
+
+use casus::CancellationToken;
+use common::{block_on_timeout, poll_once};
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+#[test]
+fn cancel_wakes_every_waiter() {
+    let token = CancellationToken::new();
+    let waiters = (0..4)
+        .map(|_| {
+            thread::spawn({
+                let token = token.clone();
+                move || block_on_timeout(token.cancelled_owned(), TIMEOUT)
+            })
+        })
+        .collect::<Vec<_>>();
+    thread::sleep(Duration::from_millis(20));
+    assert!(!token.is_cancelled());
+    token.cancel();
+    assert!(token.is_cancelled());
+    for waiter in waiters {
+        waiter.join().unwrap();
+    }
+    block_on_timeout(token.cancelled(), TIMEOUT);
+}
+
+#[test]
+fn cancel_propagates_to_descendants() {
+    let token = CancellationToken::new();
+    let child = token.child_token();
+    let grandchild = child.child_token();
+    let mut cancelled = pin!(grandchild.cancelled());
+    assert!(poll_once(cancelled.as_mut()).is_pending());
+
+    token.cancel();
+    assert!(child.is_cancelled());
+    assert!(grandchild.is_cancelled());
+    assert_eq!(poll_once(cancelled.as_mut()), Poll::Ready(()));
+}
+
+#[test]
+fn cancelling_a_child_leaves_its_parent() {
+    let token = CancellationToken::new();
+    let child = token.child_token();
+    let sibling = token.child_token();
+    child.cancel();
+    assert!(child.is_cancelled());
+    assert!(!token.is_cancelled());
+    assert!(!sibling.is_cancelled());
+}
+
+#[test]
+fn child_of_a_cancelled_token_starts_cancelled() {
+    let token = CancellationToken::new();
+    token.cancel();
+    assert!(token.child_token().child_token().is_cancelled());
+}
+
+#[test]
+fn dropped_children_are_skipped() {
+    let token = CancellationToken::new();
+    for _ in 0..100 {
+        drop(token.child_token().child_token());
+    }
+    let child = token.child_token();
+    let clone = child.clone();
+    drop(child);
+    token.cancel();
+    assert!(clone.is_cancelled());
+}
+
+#[test]
+fn run_until_cancelled_races_the_future() {
+    let token = CancellationToken::new();
+    assert_eq!(
+        block_on_timeout(token.run_until_cancelled(ready(5)), TIMEOUT),
+        Some(5)
+    );
+
+    let mut run = pin!(token.run_until_cancelled(pending::<()>()));
+    assert!(poll_once(run.as_mut()).is_pending());
+    token.cancel();
+    assert_eq!(poll_once(run.as_mut()), Poll::Ready(None));
+    // a cancelled token wins even against a future that is ready
+    assert_eq!(
+        block_on_timeout(token.run_until_cancelled(ready(5)), TIMEOUT),
+        None
+    );
+}
@@ -0,0 +1,240 @@
+mod common;
+
+use std::{future::poll_fn, pin::pin, sync::Arc, task::Poll, thread, time::Duration};
+
+use casus::{
+    channel::{broadcast, mpsc, oneshot, SendError, TryRecvError, TrySendError},
+    Closed,
+};
+use common::{block_on_timeout, poll_once};
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+#[test]
+fn oneshot_sends_a_value() {
+    let (sender, mut receiver) = oneshot::channel();
+    assert_eq!(receiver.try_recv(), Err(TryRecvError::Empty));
+    sender.send(5).unwrap();
+    assert_eq!(receiver.try_recv(), Ok(5));
+
+    let (sender, receiver) = oneshot::channel();
+    let receiver = thread::spawn(move || block_on_timeout(receiver, TIMEOUT));
+    sender.send("hello").unwrap();
+    assert_eq!(receiver.join().unwrap(), Ok("hello"));
+}
+
+#[test]
+fn oneshot_reports_closing() {
+    let (sender, mut receiver) = oneshot::channel::<u32>();
+    drop(sender);
+    assert_eq!(receiver.try_recv(), Err(TryRecvError::Closed));
+    assert_eq!(block_on_timeout(receiver, TIMEOUT), Err(Closed));
+
+    let (sender, receiver) = oneshot::channel();
+    {
+        let mut closed = pin!(sender.closed());
+        assert!(poll_once(closed.as_mut()).is_pending());
+        drop(receiver);
+        assert!(poll_once(closed.as_mut()).is_ready());
+    }
+    assert!(sender.is_closed());
+    assert_eq!(sender.send(5), Err(5));
+}
+
+#[test]
+fn mpsc_receives_in_order_from_every_sender() {
+    let (sender, mut receiver) = mpsc::channel(4);
+    let senders = (0..4)
+        .map(|i| {
+            let sender = sender.clone();
+            thread::spawn(move || {
+                for j in 0..100 {
+                    block_on_timeout(sender.send((i, j)), TIMEOUT).unwrap();
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    drop(sender);
+
+    let mut next = [0; 4];
+    while let Some((i, j)) = block_on_timeout(receiver.recv(), TIMEOUT) {
+        assert_eq!(next[i], j);
+        next[i] += 1;
+    }
+    assert_eq!(next, [100; 4]);
+    for sender in senders {
+        sender.join().unwrap();
+    }
+}
+
+#[test]
+fn mpsc_send_waits_for_space() {
+    let (sender, mut receiver) = mpsc::channel(1);
+    sender.try_send(1).unwrap();
+    assert_eq!(sender.try_send(2), Err(TrySendError::Full(2)));
+    assert_eq!(sender.capacity(), 0);
+
+    let mut send = pin!(sender.send(2));
+    assert!(poll_once(send.as_mut()).is_pending());
+    assert_eq!(receiver.try_recv(), Ok(1));
+    assert_eq!(poll_once(send.as_mut()), Poll::Ready(Ok(())));
+    assert_eq!(receiver.try_recv(), Ok(2));
+    assert_eq!(receiver.try_recv(), Err(TryRecvError::Empty));
+}
+
+#[test]
+fn mpsc_permit_holds_space_until_dropped() {
+    let (sender, mut receiver) = mpsc::channel(1);
+    let permit = block_on_timeout(sender.reserve(), TIMEOUT).unwrap();
+    assert_eq!(sender.try_send(1), Err(TrySendError::Full(1)));
+    drop(permit);
+
+    block_on_timeout(sender.reserve(), TIMEOUT)
+        .unwrap()
+        .send(2)
+        .unwrap();
+    assert_eq!(receiver.try_recv(), Ok(2));
+}
+
+fn poll_reserve<T>(sender: &mut mpsc::Sender<T>) -> Poll<Result<(), Closed>> {
+    poll_once(pin!(poll_fn(|cx| sender.poll_reserve(cx))))
+}
+
+#[test]
+fn mpsc_poll_reserve_keeps_its_place_between_polls() {
+    let (mut first, mut receiver) = mpsc::channel(1);
+    let mut second = first.clone();
+    first.try_send(1).unwrap();
+    assert!(poll_reserve(&mut first).is_pending());
+    assert!(poll_reserve(&mut second).is_pending());
+
+    assert_eq!(receiver.try_recv(), Ok(1));
+    // the space goes to the sender that started waiting first
+    assert!(poll_reserve(&mut second).is_pending());
+    assert_eq!(poll_reserve(&mut first), Poll::Ready(Ok(())));
+    assert_eq!(poll_reserve(&mut first), Poll::Ready(Ok(())));
+    first.send_item(2).unwrap();
+    assert_eq!(receiver.try_recv(), Ok(2));
+
+    // dropping a sender gives back the space it reserved
+    assert_eq!(poll_reserve(&mut second), Poll::Ready(Ok(())));
+    assert_eq!(first.capacity(), 0);
+    drop(second);
+    assert_eq!(first.capacity(), 1);
+}
+
+#[test]
+fn mpsc_close_fails_pending_reserves() {
+    let (mut sender, mut receiver) = mpsc::channel(1);
+    sender.try_send(1).unwrap();
+    assert!(poll_reserve(&mut sender).is_pending());
+    receiver.close();
+    assert_eq!(poll_reserve(&mut sender), Poll::Ready(Err(Closed)));
+    assert!(!sender.abort_reserve());
+}
+
+#[test]
+fn mpsc_close_fails_waiting_sends() {
+    let (sender, mut receiver) = mpsc::channel(1);
+    sender.try_send(1).unwrap();
+    let mut send = pin!(sender.send(2));
+    assert!(poll_once(send.as_mut()).is_pending());
+
+    receiver.close();
+    assert_eq!(poll_once(send.as_mut()), Poll::Ready(Err(SendError(2))));
+    assert!(sender.is_closed());
+    assert_eq!(sender.try_send(3), Err(TrySendError::Closed(3)));
+    // values sent before closing can still be received
+    assert_eq!(block_on_timeout(receiver.recv(), TIMEOUT), Some(1));
+    assert_eq!(block_on_timeout(receiver.recv(), TIMEOUT), None);
+}
+
+#[test]
+fn mpsc_recv_ends_when_senders_are_dropped() {
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+    {
+        let mut recv = pin!(receiver.recv());
+        assert!(poll_once(recv.as_mut()).is_pending());
+        sender.send(1).unwrap();
+        assert_eq!(poll_once(recv.as_mut()), Poll::Ready(Some(1)));
+    }
+
+    let other = sender.clone();
+    drop(sender);
+    other.send(2).unwrap();
+    drop(other);
+    assert_eq!(receiver.try_recv(), Ok(2));
+    assert_eq!(receiver.try_recv(), Err(TryRecvError::Closed));
+}
+
+#[test]
+fn mpsc_dropping_the_receiver_drops_queued_values() {
+    let value = Arc::new(());
+    let (sender, receiver) = mpsc::unbounded_channel();
+    sender.send(value.clone()).unwrap();
+    drop(receiver);
+    assert_eq!(Arc::strong_count(&value), 1);
+    assert_eq!(sender.send(value.clone()), Err(SendError(value.clone())));
+}
+
+#[test]
+fn broadcast_gives_every_receiver_every_value() {
+    let (sender, first) = broadcast::channel(16);
+    let receivers = [first, sender.subscribe(), sender.subscribe()].map(|mut receiver| {
+        thread::spawn(move || {
+            let mut values = Vec::new();
+            while let Ok(v) = block_on_timeout(receiver.recv(), TIMEOUT) {
+                values.push(v);
+            }
+            values
+        })
+    });
+    assert_eq!(sender.receiver_count(), 3);
+    for i in 0..10 {
+        assert_eq!(sender.send(i), Ok(3));
+        thread::sleep(Duration::from_millis(1));
+    }
+    drop(sender);
+    for receiver in receivers {
+        assert_eq!(receiver.join().unwrap(), (0..10).collect::<Vec<_>>());
+    }
+}
+
+#[test]
+fn broadcast_reports_lag() {
+    let (sender, mut receiver) = broadcast::channel(2);
+    for i in 0..5 {
+        sender.send(i).unwrap();
+    }
+    assert_eq!(receiver.try_recv(), Err(broadcast::TryRecvError::Lagged(3)));
+    assert_eq!(receiver.try_recv(), Ok(3));
+    assert_eq!(block_on_timeout(receiver.recv(), TIMEOUT), Ok(4));
+    assert_eq!(receiver.try_recv(), Err(broadcast::TryRecvError::Empty));
+
+    sender.send(5).unwrap();
+    sender.send(6).unwrap();
+    sender.send(7).unwrap();
+    assert_eq!(
+        block_on_timeout(receiver.recv(), TIMEOUT),
+        Err(broadcast::RecvError::Lagged(1))
+    );
+}
+
+#[test]
+fn broadcast_send_fails_without_receivers() {
+    let (sender, receiver) = broadcast::channel(2);
+    let mut later = sender.subscribe();
+    drop(receiver);
+    assert_eq!(sender.send(1), Ok(1));
+    drop(later);
+    assert_eq!(sender.send(2), Err(SendError(2)));
+
+    later = sender.subscribe();
+    let mut recv = pin!(later.recv());
+    assert!(poll_once(recv.as_mut()).is_pending());
+    drop(sender);
+    assert_eq!(
+        poll_once(recv.as_mut()),
+        Poll::Ready(Err(broadcast::RecvError::Closed))
+    );
+}
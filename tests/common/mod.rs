@@ -0,0 +1,45 @@
+// not every test binary uses every helper
+#![allow(dead_code)]
+
+use std::{
+    future::Future,
+    pin::{pin, Pin},
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
+    thread::{self, Thread},
+    time::{Duration, Instant},
+};
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Drives `fut` to completion on the current thread, panicking if it hasn't completed within
+/// `timeout` so that a lost wakeup fails the test instead of hanging it
+pub fn block_on_timeout<F: Future>(fut: F, timeout: Duration) -> F::Output {
+    let deadline = Instant::now() + timeout;
+    let mut fut = pin!(fut);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        if let Poll::Ready(v) = fut.as_mut().poll(&mut cx) {
+            return v;
+        }
+        let now = Instant::now();
+        assert!(
+            now < deadline,
+            "future did not complete within {:?}",
+            timeout
+        );
+        thread::park_timeout(deadline - now);
+    }
+}
+
+/// Polls `fut` once without a waker, for checking a future's state at a particular point
+pub fn poll_once<F: Future + ?Sized>(fut: Pin<&mut F>) -> Poll<F::Output> {
+    fut.poll(&mut Context::from_waker(Waker::noop()))
+}
@@ -0,0 +1,119 @@
+mod common;
+
+use std::{pin::pin, sync::Arc, task::Poll, thread, time::Duration};
+
+use casus::{AutoResetSource, CompositeEvent, Event, EventSource, WaitResult};
+use common::{block_on_timeout, poll_once};
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+fn events<const N: usize>() -> [Arc<Event>; N] {
+    std::array::from_fn(|_| Arc::new(Event::new()))
+}
+
+#[test]
+fn all_tracks_every_source() {
+    let [db, cache] = events();
+    let ready = CompositeEvent::all([db.clone(), cache.clone()]).unwrap();
+    assert!(!ready.is_set());
+    db.set().unwrap();
+    assert!(!ready.is_set());
+    cache.set().unwrap();
+    assert!(ready.is_set());
+    db.clear().unwrap();
+    assert!(!ready.is_set());
+}
+
+#[test]
+fn any_tracks_every_source() {
+    let [shutdown, reload] = events();
+    let stop = CompositeEvent::any([shutdown.clone(), reload.clone()]).unwrap();
+    assert!(!stop.is_set());
+    reload.set().unwrap();
+    assert!(stop.is_set());
+    reload.clear().unwrap();
+    assert!(!stop.is_set());
+}
+
+#[test]
+fn all_waits_for_sources_cleared_while_waiting() {
+    let [db, cache] = events();
+    let ready = CompositeEvent::all([db.clone(), cache.clone()]).unwrap();
+    let mut wait = pin!(ready.wait());
+    assert!(poll_once(wait.as_mut()).is_pending());
+
+    db.set().unwrap();
+    assert!(poll_once(wait.as_mut()).is_pending());
+    db.clear().unwrap();
+    cache.set().unwrap();
+    assert!(poll_once(wait.as_mut()).is_pending());
+    db.set().unwrap();
+    assert_eq!(poll_once(wait.as_mut()), Poll::Ready(Ok(WaitResult::Set)));
+}
+
+#[test]
+fn any_wakes_on_the_first_source() {
+    let sources = events::<3>();
+    let stop = CompositeEvent::any(sources.clone()).unwrap();
+    let waiter = {
+        let stop = stop.clone();
+        thread::spawn(move || block_on_timeout(stop.wait(), TIMEOUT))
+    };
+    thread::sleep(Duration::from_millis(10));
+    sources[2].set().unwrap();
+    assert_eq!(waiter.join().unwrap(), Ok(WaitResult::Set));
+    // composites don't consume their sources
+    assert!(sources[2].is_set());
+}
+
+#[test]
+fn empty_composites() {
+    assert!(CompositeEvent::all::<Event>([]).unwrap().is_set());
+    assert_eq!(
+        block_on_timeout(CompositeEvent::all::<Event>([]).unwrap().wait(), TIMEOUT),
+        Ok(WaitResult::AlreadySet)
+    );
+    assert!(!CompositeEvent::any::<Event>([]).unwrap().is_set());
+}
+
+#[test]
+fn wait_fails_on_closed_sources() {
+    let [db, cache] = events();
+    let ready = CompositeEvent::all([db.clone(), cache.clone()]).unwrap();
+    let mut wait = pin!(ready.wait());
+    assert!(poll_once(wait.as_mut()).is_pending());
+    db.poison("connection lost").unwrap();
+    match poll_once(wait.as_mut()) {
+        Poll::Ready(Err(closed)) => assert_eq!(closed.reason(), Some("connection lost")),
+        poll => panic!("unexpected {:?}", poll),
+    }
+}
+
+#[test]
+fn auto_reset_sources_are_rejected() {
+    assert_eq!(
+        CompositeEvent::any([Arc::new(Event::auto_reset())]).unwrap_err(),
+        AutoResetSource
+    );
+}
+
+#[test]
+fn composites_can_be_sources() {
+    let [db, cache, shutdown] = events();
+    let ready = CompositeEvent::all([db.clone(), cache.clone()]).unwrap();
+    let stop = CompositeEvent::any([ready.clone()]).unwrap();
+    let mut wait = pin!(stop.wait());
+    assert!(poll_once(wait.as_mut()).is_pending());
+    db.set().unwrap();
+    assert!(poll_once(wait.as_mut()).is_pending());
+    cache.set().unwrap();
+    assert_eq!(poll_once(wait.as_mut()), Poll::Ready(Ok(WaitResult::Set)));
+
+    // composites and events can be waited on together
+    db.clear().unwrap();
+    let sources: [&dyn EventSource; 2] = [&*shutdown, &ready];
+    let mut any = pin!(Event::wait_any(&sources));
+    assert!(poll_once(any.as_mut()).is_pending());
+    db.set().unwrap();
+    assert_eq!(poll_once(any.as_mut()), Poll::Ready(Ok(1)));
+}
@@ -0,0 +1,117 @@
+mod common;
+
+use std::{pin::pin, sync::Arc, task::Poll, thread, time::Duration};
+
+use casus::{Condvar, Mutex};
+use common::{block_on_timeout, poll_once};
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+#[cfg(feature = "std")]
+fn assert_send<F: std::future::Future + Send>(fut: F) -> F {
+    fut
+}
+
+#[test]
+fn wait_releases_the_lock() {
+    let mutex = Mutex::new(0);
+    let condvar = Condvar::new();
+    let guard = block_on_timeout(mutex.lock(), TIMEOUT);
+    let mut wait = pin!(condvar.wait(guard));
+    assert!(poll_once(wait.as_mut()).is_pending());
+
+    *mutex.try_lock().unwrap() = 1;
+    condvar.notify_one();
+    match poll_once(wait.as_mut()) {
+        Poll::Ready(guard) => assert_eq!(*guard, 1),
+        Poll::Pending => panic!("the wait wasn't notified"),
+    };
+}
+
+#[test]
+fn wait_while_checks_the_condition() {
+    let state = Arc::new((Mutex::new(0), Condvar::new()));
+    let waiter = {
+        let state = state.clone();
+        thread::spawn(move || {
+            let (mutex, condvar) = &*state;
+            let wait = async { *condvar.wait_while(mutex.lock().await, |n| *n < 3).await };
+            block_on_timeout(wait, TIMEOUT)
+        })
+    };
+    for _ in 0..3 {
+        thread::sleep(Duration::from_millis(5));
+        let (mutex, condvar) = &*state;
+        *block_on_timeout(mutex.lock(), TIMEOUT) += 1;
+        condvar.notify_all();
+    }
+    assert_eq!(waiter.join().unwrap(), 3);
+}
+
+#[test]
+fn notify_all_wakes_every_waiter() {
+    let mutex = Mutex::new(());
+    let condvar = Condvar::new();
+    let mut first = pin!(condvar.wait(mutex.try_lock().unwrap()));
+    assert!(poll_once(first.as_mut()).is_pending());
+    let mut second = pin!(condvar.wait(mutex.try_lock().unwrap()));
+    assert!(poll_once(second.as_mut()).is_pending());
+
+    condvar.notify_all();
+    let Poll::Ready(guard) = poll_once(first.as_mut()) else {
+        panic!("the first wait wasn't notified");
+    };
+    // the second waiter was notified too, it's only waiting for the first to unlock
+    assert!(poll_once(second.as_mut()).is_pending());
+    drop(guard);
+    assert!(poll_once(second.as_mut()).is_ready());
+}
+
+#[test]
+fn dropped_waiter_hands_on_notify_one() {
+    let mutex = Mutex::new(());
+    let condvar = Condvar::new();
+    let mut first = Box::pin(condvar.wait(mutex.try_lock().unwrap()));
+    assert!(poll_once(first.as_mut()).is_pending());
+    let mut second = pin!(condvar.wait(mutex.try_lock().unwrap()));
+    assert!(poll_once(second.as_mut()).is_pending());
+
+    condvar.notify_one();
+    drop(first);
+    assert!(poll_once(second.as_mut()).is_ready());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn wait_std_releases_the_lock() {
+    let mutex = std::sync::Mutex::new(0);
+    let condvar = Condvar::new();
+    let mut wait = pin!(assert_send(condvar.wait_std(&mutex, mutex.lock().unwrap())));
+
+    *mutex.lock().unwrap() = 1;
+    assert!(poll_once(wait.as_mut()).is_pending());
+    condvar.notify_one();
+    match poll_once(wait.as_mut()) {
+        Poll::Ready(guard) => assert_eq!(*guard.unwrap(), 1),
+        Poll::Pending => panic!("the wait wasn't notified"),
+    };
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn wait_while_std_wakes_across_threads() {
+    let state = Arc::new((std::sync::Mutex::new(Vec::new()), Condvar::new()));
+    let waiter = {
+        let state = state.clone();
+        thread::spawn(move || {
+            let (mutex, condvar) = &*state;
+            let wait = assert_send(condvar.wait_while_std(mutex, |queue| queue.is_empty()));
+            block_on_timeout(wait, TIMEOUT).unwrap().pop()
+        })
+    };
+    thread::sleep(Duration::from_millis(10));
+    let (mutex, condvar) = &*state;
+    mutex.lock().unwrap().push(7);
+    condvar.notify_one();
+    assert_eq!(waiter.join().unwrap(), Some(7));
+}
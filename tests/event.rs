@@ -0,0 +1,403 @@
+mod common;
+
+#[cfg(feature = "std")]
+use std::time::Instant;
+use std::{
+    future::Future,
+    pin::{pin, Pin},
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Wake, Waker},
+    thread,
+    time::Duration,
+};
+
+use casus::{Event, EventClosed, EventWait, OwnedEventWait, WaitResult};
+use common::{block_on_timeout, poll_once};
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+#[test]
+fn set_wakes_waiters_on_other_threads() {
+    // races `set` against threads that are just starting to wait, any wakeup lost between the
+    // state check and the waiter registration leaves a waiter hanging until the timeout
+    for _ in 0..1000 {
+        let event = Arc::new(Event::new());
+        let waiters = (0..4)
+            .map(|_| {
+                let event = event.clone();
+                thread::spawn(move || block_on_timeout(event.wait(), TIMEOUT))
+            })
+            .collect::<Vec<_>>();
+        event.set().unwrap();
+        for waiter in waiters {
+            assert!(matches!(
+                waiter.join().unwrap(),
+                Ok(WaitResult::AlreadySet | WaitResult::Set)
+            ));
+        }
+    }
+}
+
+#[test]
+fn set_and_clear_race_with_waiters() {
+    let event = Arc::new(Event::new());
+    let setter = {
+        let event = event.clone();
+        thread::spawn(move || {
+            for _ in 0..10_000 {
+                event.set().unwrap();
+                event.clear().unwrap();
+            }
+            event.set().unwrap();
+        })
+    };
+    let waiters = (0..4)
+        .map(|_| {
+            let event = event.clone();
+            thread::spawn(move || {
+                for _ in 0..1000 {
+                    block_on_timeout(event.wait(), TIMEOUT).unwrap();
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    setter.join().unwrap();
+    for waiter in waiters {
+        waiter.join().unwrap();
+    }
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn wait_timeout_times_out_when_not_set() {
+    let event = Event::new();
+    let start = Instant::now();
+    let result = block_on_timeout(event.wait_timeout(Duration::from_millis(50)), TIMEOUT);
+    assert_eq!(result, Ok(WaitResult::TimedOut));
+    assert!(start.elapsed() >= Duration::from_millis(50));
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn wait_timeout_returns_once_set() {
+    let event = Arc::new(Event::new());
+    let setter = {
+        let event = event.clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(20));
+            event.set().unwrap();
+        })
+    };
+    let result = block_on_timeout(event.wait_timeout(TIMEOUT), TIMEOUT * 2);
+    assert_eq!(result, Ok(WaitResult::Set));
+    setter.join().unwrap();
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn blocking_and_async_waiters_share_an_event() {
+    let event = Arc::new(Event::new());
+    let blocking = (0..2)
+        .map(|_| {
+            let event = event.clone();
+            thread::spawn(move || event.wait_blocking_timeout(TIMEOUT))
+        })
+        .collect::<Vec<_>>();
+    let waiting = (0..2)
+        .map(|_| {
+            let event = event.clone();
+            thread::spawn(move || block_on_timeout(event.wait(), TIMEOUT))
+        })
+        .collect::<Vec<_>>();
+    thread::sleep(Duration::from_millis(20));
+    event.set().unwrap();
+    for handle in blocking {
+        assert_eq!(handle.join().unwrap(), Ok(WaitResult::Set));
+    }
+    for handle in waiting {
+        assert_eq!(handle.join().unwrap(), Ok(WaitResult::Set));
+    }
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn wait_blocking_timeout_times_out_when_not_set() {
+    let event = Event::new();
+    let start = Instant::now();
+    assert_eq!(
+        event.wait_blocking_timeout(Duration::from_millis(50)),
+        Ok(WaitResult::TimedOut)
+    );
+    assert!(start.elapsed() >= Duration::from_millis(50));
+}
+
+#[test]
+fn auto_reset_releases_one_waiter_per_set_in_order() {
+    let event = Event::auto_reset();
+    let mut first = pin!(event.wait());
+    let mut second = pin!(event.wait());
+    assert!(poll_once(first.as_mut()).is_pending());
+    assert!(poll_once(second.as_mut()).is_pending());
+    event.set().unwrap();
+    assert!(!event.is_set());
+    assert!(poll_once(second.as_mut()).is_pending());
+    assert_eq!(poll_once(first.as_mut()), Poll::Ready(Ok(WaitResult::Set)));
+    event.set().unwrap();
+    assert_eq!(poll_once(second.as_mut()), Poll::Ready(Ok(WaitResult::Set)));
+    assert!(!event.is_set());
+}
+
+#[test]
+fn auto_reset_keeps_the_set_for_the_next_waiter() {
+    let event = Event::auto_reset();
+    event.set().unwrap();
+    assert!(event.is_set());
+    assert_eq!(
+        block_on_timeout(event.wait(), TIMEOUT),
+        Ok(WaitResult::AlreadySet)
+    );
+    assert!(!event.is_set());
+    assert!(poll_once(pin!(event.wait())).is_pending());
+}
+
+#[test]
+fn auto_reset_passes_on_the_release_of_a_dropped_waiter() {
+    let event = Event::auto_reset();
+    let mut first = Box::pin(event.wait());
+    let mut second = pin!(event.wait());
+    assert!(poll_once(first.as_mut()).is_pending());
+    assert!(poll_once(second.as_mut()).is_pending());
+    event.set().unwrap();
+    drop(first);
+    assert_eq!(poll_once(second.as_mut()), Poll::Ready(Ok(WaitResult::Set)));
+    assert!(!event.is_set());
+}
+
+#[test]
+fn pulse_wakes_current_waiters_without_setting() {
+    let event = Event::new();
+    let mut first = pin!(event.wait());
+    let mut second = pin!(event.wait());
+    assert!(poll_once(first.as_mut()).is_pending());
+    assert!(poll_once(second.as_mut()).is_pending());
+    event.pulse().unwrap();
+    assert!(!event.is_set());
+    assert_eq!(
+        poll_once(first.as_mut()),
+        Poll::Ready(Ok(WaitResult::Pulsed))
+    );
+    assert_eq!(
+        poll_once(second.as_mut()),
+        Poll::Ready(Ok(WaitResult::Pulsed))
+    );
+    assert!(poll_once(pin!(event.wait())).is_pending());
+}
+
+#[test]
+fn pulse_resets_a_set_event() {
+    let event = Event::new();
+    event.set().unwrap();
+    event.pulse().unwrap();
+    assert!(!event.is_set());
+}
+
+#[test]
+fn wait_reports_an_already_set_event() {
+    let event = Event::new();
+    event.set().unwrap();
+    assert_eq!(
+        poll_once(pin!(event.wait())),
+        Poll::Ready(Ok(WaitResult::AlreadySet))
+    );
+}
+
+#[test]
+fn close_fails_current_and_future_waits() {
+    let event = Arc::new(Event::new());
+    let waiters = (0..4)
+        .map(|_| {
+            let event = event.clone();
+            thread::spawn(move || block_on_timeout(event.wait(), TIMEOUT))
+        })
+        .collect::<Vec<_>>();
+    thread::sleep(Duration::from_millis(20));
+    event.close().unwrap();
+    for waiter in waiters {
+        let closed = waiter.join().unwrap().unwrap_err();
+        assert_eq!(closed.reason(), None);
+    }
+    assert!(event.is_closed());
+    assert!(block_on_timeout(event.wait(), TIMEOUT).is_err());
+}
+
+#[test]
+fn poison_carries_the_reason() {
+    let event = Event::new();
+    event.set().unwrap();
+    event.poison("dependency lost").unwrap();
+    assert!(!event.is_set());
+    let closed = poll_once(pin!(event.wait()));
+    let Poll::Ready(Err(closed)) = closed else {
+        panic!("expected the wait to fail, got {:?}", closed);
+    };
+    assert_eq!(closed.reason(), Some("dependency lost"));
+    assert_eq!(closed.to_string(), "event closed: dependency lost");
+}
+
+#[test]
+fn closed_events_reject_changes() {
+    let event = Event::auto_reset();
+    event.poison("gone").unwrap();
+    let closed = event.close().unwrap_err();
+    assert_eq!(closed.reason(), Some("gone"));
+    let closed: Result<(), EventClosed> = Err(closed);
+    assert_eq!(event.set(), closed);
+    assert_eq!(event.clear(), closed);
+    assert_eq!(event.pulse(), closed);
+    assert!(!event.is_set());
+}
+
+#[test]
+fn wait_futures_can_be_named_and_stored() {
+    fn assert_send_unpin<F: Future + Send + Unpin>(_: &F) {}
+
+    struct Stored<'a> {
+        wait: EventWait<'a>,
+    }
+
+    let event = Arc::new(Event::new());
+    let mut stored = Stored { wait: event.wait() };
+    assert_send_unpin(&stored.wait);
+    assert!(poll_once(Pin::new(&mut stored.wait)).is_pending());
+    let owned: OwnedEventWait = event.clone().wait_owned();
+    assert_send_unpin(&owned);
+    let waiting = thread::spawn(move || block_on_timeout(owned, TIMEOUT));
+    event.set().unwrap();
+    assert_eq!(
+        poll_once(Pin::new(&mut stored.wait)),
+        Poll::Ready(Ok(WaitResult::Set))
+    );
+    assert!(matches!(
+        waiting.join().unwrap(),
+        Ok(WaitResult::AlreadySet | WaitResult::Set)
+    ));
+}
+
+#[test]
+fn wait_any_returns_the_first_set_event() {
+    let (first, second, third) = (Event::new(), Event::new(), Event::new());
+    let mut wait = pin!(Event::wait_any(&[&first, &second, &third]));
+    assert!(poll_once(wait.as_mut()).is_pending());
+
+    second.set().unwrap();
+    assert_eq!(poll_once(wait.as_mut()), Poll::Ready(Ok(1)));
+
+    third.set().unwrap();
+    assert_eq!(
+        block_on_timeout(Event::wait_any(&[&first, &third]), TIMEOUT),
+        Ok(1)
+    );
+}
+
+#[test]
+fn wait_any_only_resets_the_returned_event() {
+    let (first, second) = (Event::auto_reset(), Event::auto_reset());
+    {
+        let mut wait = pin!(Event::wait_any(&[&first, &second]));
+        assert!(poll_once(wait.as_mut()).is_pending());
+        first.set().unwrap();
+        second.set().unwrap();
+        assert_eq!(poll_once(wait.as_mut()), Poll::Ready(Ok(0)));
+    }
+    assert!(!first.is_set());
+    // the release handed to the second event's registration was passed back when it was dropped
+    assert!(second.is_set());
+}
+
+#[test]
+fn wait_all_waits_for_every_event() {
+    let events = Arc::new([Event::new(), Event::new(), Event::new()]);
+    let waiter = {
+        let events = events.clone();
+        thread::spawn(move || {
+            let [first, second, third] = &*events;
+            block_on_timeout(Event::wait_all(&[first, second, third]), TIMEOUT)
+        })
+    };
+    for event in events.iter() {
+        thread::sleep(Duration::from_millis(5));
+        assert!(!waiter.is_finished());
+        event.set().unwrap();
+    }
+    assert_eq!(waiter.join().unwrap(), Ok(()));
+    block_on_timeout(Event::wait_all(&[]), TIMEOUT).unwrap();
+}
+
+#[test]
+fn cancelled_wait_all_hands_back_auto_reset_releases() {
+    let (first, second) = (Event::auto_reset(), Event::new());
+    first.set().unwrap();
+    {
+        let mut wait = pin!(Event::wait_all(&[&first, &second]));
+        assert!(poll_once(wait.as_mut()).is_pending());
+        assert!(!first.is_set());
+    }
+    assert!(first.is_set());
+
+    // a failed wait hands its releases back as well
+    let mut wait = pin!(Event::wait_all(&[&first, &second]));
+    assert!(poll_once(wait.as_mut()).is_pending());
+    second.close().unwrap();
+    assert!(matches!(poll_once(wait.as_mut()), Poll::Ready(Err(_))));
+    assert!(first.is_set());
+
+    // while a completed one keeps them
+    let third = Event::new();
+    third.set().unwrap();
+    block_on_timeout(Event::wait_all(&[&first, &third]), TIMEOUT).unwrap();
+    assert!(!first.is_set());
+}
+
+#[test]
+fn multi_waits_fail_on_closed_events() {
+    let (first, second) = (Event::new(), Event::new());
+    let mut any = pin!(Event::wait_any(&[&first, &second]));
+    let mut all = pin!(Event::wait_all(&[&first, &second]));
+    assert!(poll_once(any.as_mut()).is_pending());
+    assert!(poll_once(all.as_mut()).is_pending());
+
+    first.set().unwrap();
+    assert!(poll_once(all.as_mut()).is_pending());
+    second.poison("shutting down").unwrap();
+    assert_eq!(poll_once(any.as_mut()), Poll::Ready(Ok(0)));
+    assert!(matches!(poll_once(all.as_mut()), Poll::Ready(Err(_))));
+}
+
+#[test]
+fn wakers_can_drop_their_wait_inline() {
+    let event = Arc::new(Event::auto_reset());
+    let dropping = Arc::new(DroppingWaker(Mutex::new(None)));
+    let waker = Waker::from(dropping.clone());
+    let mut wait = Box::pin(event.clone().wait_owned());
+    assert!(wait
+        .as_mut()
+        .poll(&mut Context::from_waker(&waker))
+        .is_pending());
+    *dropping.0.lock().unwrap() = Some(wait);
+
+    // dropping the wait locks the event again, so this only returns if set wakes it after
+    // unlocking
+    event.set().unwrap();
+    assert!(dropping.0.lock().unwrap().is_none());
+    // the wait was dropped before it could return, so it handed its release back
+    assert!(event.is_set());
+}
+
+/// A waker that drops the wait it was registered by as soon as it's woken
+struct DroppingWaker(Mutex<Option<Pin<Box<OwnedEventWait>>>>);
+
+impl Wake for DroppingWaker {
+    fn wake(self: Arc<Self>) {
+        drop(self.0.lock().unwrap().take());
+    }
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use std::{
+    future::{pending, ready},
+    pin::pin,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    task::Poll,
+    thread,
+    time::Duration,
+};
+
+use casus::Latch;
+use common::{block_on_timeout, poll_once};
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+#[test]
+fn set_succeeds_once() {
+    let latch = Latch::new();
+    assert_eq!(latch.try_get(), None);
+    assert_eq!(latch.set(1), Ok(()));
+    assert_eq!(latch.set(2), Err(2));
+    assert_eq!(latch.try_get(), Some(&1));
+    assert_eq!(latch.into_inner(), Some(1));
+}
+
+#[test]
+fn get_wakes_every_waiter() {
+    let latch = Arc::new(Latch::new());
+    let waiters = (0..4)
+        .map(|_| {
+            let latch = latch.clone();
+            thread::spawn(move || *block_on_timeout(latch.get(), TIMEOUT))
+        })
+        .collect::<Vec<_>>();
+    thread::sleep(Duration::from_millis(20));
+    latch.set(7).unwrap();
+    for waiter in waiters {
+        assert_eq!(waiter.join().unwrap(), 7);
+    }
+    assert_eq!(block_on_timeout(latch.get(), TIMEOUT), &7);
+}
+
+#[test]
+fn get_or_init_initializes_once() {
+    let latch = Arc::new(Latch::new());
+    let inits = Arc::new(AtomicUsize::new(0));
+    let callers = (0..8)
+        .map(|i| {
+            let latch = latch.clone();
+            let inits = inits.clone();
+            thread::spawn(move || {
+                let init = || {
+                    inits.fetch_add(1, Ordering::SeqCst);
+                    ready(i)
+                };
+                *block_on_timeout(latch.get_or_init(init), TIMEOUT)
+            })
+        })
+        .collect::<Vec<_>>();
+    let values = callers
+        .into_iter()
+        .map(|caller| caller.join().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(inits.load(Ordering::SeqCst), 1);
+    assert!(values.iter().all(|v| *v == values[0]));
+}
+
+#[test]
+fn abandoned_initialization_is_taken_over() {
+    let latch = Latch::new();
+    let mut first = Box::pin(latch.get_or_init(pending));
+    assert!(poll_once(first.as_mut()).is_pending());
+    let mut second = pin!(latch.get_or_init(|| ready(2)));
+    let mut getter = pin!(latch.get());
+    assert!(poll_once(second.as_mut()).is_pending());
+    assert!(poll_once(getter.as_mut()).is_pending());
+    drop(first);
+    assert!(matches!(poll_once(second.as_mut()), Poll::Ready(&2)));
+    assert!(matches!(poll_once(getter.as_mut()), Poll::Ready(&2)));
+}
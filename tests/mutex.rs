@@ -0,0 +1,92 @@
+mod common;
+
+use std::{
+    panic::{self, AssertUnwindSafe},
+    pin::pin,
+    sync::Arc,
+    thread,
+    time::Duration,
+};
+
+use casus::{MappedMutexGuard, Mutex, MutexGuard, OwnedMutexGuard};
+use common::{block_on_timeout, poll_once};
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+#[test]
+fn lock_excludes_other_threads() {
+    let mutex = Arc::new(Mutex::new(0));
+    let tasks = (0..8)
+        .map(|_| {
+            let mutex = mutex.clone();
+            thread::spawn(move || {
+                for _ in 0..100 {
+                    let mut value = block_on_timeout(mutex.lock(), TIMEOUT);
+                    let read = *value;
+                    thread::yield_now();
+                    *value = read + 1;
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for task in tasks {
+        task.join().unwrap();
+    }
+    assert_eq!(Arc::try_unwrap(mutex).unwrap().into_inner(), 800);
+}
+
+#[test]
+fn try_lock_fails_while_locked() {
+    let mutex = Mutex::new(1);
+    let guard = mutex.try_lock().unwrap();
+    assert!(mutex.try_lock().is_none());
+    let mut waiting = pin!(mutex.lock());
+    assert!(poll_once(waiting.as_mut()).is_pending());
+    drop(guard);
+    assert!(poll_once(waiting.as_mut()).is_ready());
+}
+
+#[test]
+fn mapped_guards_keep_the_mutex_locked() {
+    let mutex = Arc::new(Mutex::new((String::from("name"), 0)));
+    let mut name = MutexGuard::map(mutex.try_lock().unwrap(), |(name, _)| name);
+    name.push('s');
+    assert!(mutex.try_lock().is_none());
+    drop(name);
+    let count = OwnedMutexGuard::map(mutex.clone().try_lock_owned().unwrap(), |(_, count)| count);
+    assert!(mutex.try_lock().is_none());
+    drop(count);
+    assert_eq!(mutex.try_lock().unwrap().0, "names");
+}
+
+#[test]
+fn panicking_maps_unlock_the_mutex() {
+    let mutex = Arc::new(Mutex::new(0));
+    let mapped = panic::catch_unwind(AssertUnwindSafe(|| {
+        MutexGuard::map(mutex.try_lock().unwrap(), |_| -> &mut u32 { panic!("map") })
+    }));
+    assert!(mapped.is_err());
+    assert!(mutex.try_lock().is_some());
+    let mapped = panic::catch_unwind(AssertUnwindSafe(|| {
+        OwnedMutexGuard::map(mutex.clone().try_lock_owned().unwrap(), |_| -> &mut u32 {
+            panic!("map")
+        })
+    }));
+    assert!(mapped.is_err());
+    assert!(mutex.try_lock().is_some());
+    let mapped = panic::catch_unwind(AssertUnwindSafe(|| {
+        let guard = MutexGuard::map(mutex.try_lock().unwrap(), |value| value);
+        MappedMutexGuard::map(guard, |_| -> &mut u32 { panic!("map") })
+    }));
+    assert!(mapped.is_err());
+    assert!(mutex.try_lock().is_some());
+}
+
+#[test]
+fn owned_guards_can_move_to_other_threads() {
+    let mutex = Arc::new(Mutex::new(Vec::new()));
+    let mut guard = block_on_timeout(mutex.clone().lock_owned(), TIMEOUT);
+    let pushing = thread::spawn(move || guard.push(1));
+    pushing.join().unwrap();
+    assert_eq!(*block_on_timeout(mutex.lock(), TIMEOUT), [1]);
+}
@@ -0,0 +1,105 @@
+mod common;
+
+use std::{pin::pin, sync::Arc, task::Poll, thread, time::Duration};
+
+use casus::{Barrier, Notify};
+use common::{block_on_timeout, poll_once};
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+#[test]
+fn notify_one_wakes_waiters_in_order() {
+    let notify = Notify::new();
+    let mut first = pin!(notify.notified());
+    let mut second = pin!(notify.notified());
+    assert!(poll_once(first.as_mut()).is_pending());
+    assert!(poll_once(second.as_mut()).is_pending());
+
+    notify.notify_one();
+    assert_eq!(poll_once(first.as_mut()), Poll::Ready(()));
+    assert!(poll_once(second.as_mut()).is_pending());
+    notify.notify_one();
+    assert_eq!(poll_once(second.as_mut()), Poll::Ready(()));
+}
+
+#[test]
+fn notify_one_stores_a_single_permit() {
+    let notify = Notify::new();
+    notify.notify_one();
+    notify.notify_one();
+    block_on_timeout(notify.notified(), TIMEOUT);
+
+    let mut notified = pin!(notify.notified());
+    assert!(poll_once(notified.as_mut()).is_pending());
+}
+
+#[test]
+fn notify_waiters_does_not_store_a_permit() {
+    let notify = Notify::new();
+    let mut first = pin!(notify.notified());
+    assert!(poll_once(first.as_mut()).is_pending());
+    // created before the call, so it's notified without having been polled
+    let mut second = pin!(notify.notified());
+
+    notify.notify_waiters();
+    assert_eq!(poll_once(first.as_mut()), Poll::Ready(()));
+    assert_eq!(poll_once(second.as_mut()), Poll::Ready(()));
+
+    let mut third = pin!(notify.notified());
+    assert!(poll_once(third.as_mut()).is_pending());
+}
+
+#[test]
+fn enable_registers_before_polling() {
+    let notify = Notify::new();
+    let mut notified = notify.notified();
+    assert!(!notified.enable());
+    notify.notify_one();
+    assert!(notified.enable());
+    block_on_timeout(notified, TIMEOUT);
+
+    // the notification went to the registered waiter, so no permit was left behind
+    assert!(!notify.notified().enable());
+
+    // without a waiter to take it, a notification becomes a permit
+    notify.notify_one();
+    assert!(notify.notified().enable());
+}
+
+#[test]
+fn dropped_waiter_hands_on_its_notification() {
+    let notify = Notify::new();
+    let mut second = pin!(notify.notified());
+    {
+        let mut first = notify.notified();
+        first.enable();
+        assert!(poll_once(second.as_mut()).is_pending());
+        notify.notify_one();
+    }
+    assert_eq!(poll_once(second.as_mut()), Poll::Ready(()));
+}
+
+#[test]
+fn notify_one_wakes_across_threads() {
+    let notify = Arc::new(Notify::new());
+    let barrier = Arc::new(Barrier::new(5));
+    let waiters = (0..4)
+        .map(|_| {
+            let notify = notify.clone();
+            let barrier = barrier.clone();
+            thread::spawn(move || {
+                let mut notified = notify.notified();
+                notified.enable();
+                block_on_timeout(barrier.wait(), TIMEOUT);
+                block_on_timeout(notified, TIMEOUT);
+            })
+        })
+        .collect::<Vec<_>>();
+    block_on_timeout(barrier.wait(), TIMEOUT);
+    for _ in 0..4 {
+        notify.notify_one();
+    }
+    for waiter in waiters {
+        waiter.join().unwrap();
+    }
+}
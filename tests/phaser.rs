@@ -0,0 +1,104 @@
+mod common;
+
+use std::{pin::pin, sync::Arc, task::Poll, thread, time::Duration};
+
+use casus::Phaser;
+use common::{block_on_timeout, poll_once};
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+#[test]
+fn arrive_and_wait_advances_the_phase() {
+    let phaser = Phaser::new(2);
+    let mut first = pin!(phaser.arrive_and_wait());
+    assert!(poll_once(first.as_mut()).is_pending());
+    assert_eq!(phaser.arrived(), 1);
+
+    assert_eq!(block_on_timeout(phaser.arrive_and_wait(), TIMEOUT), 1);
+    assert_eq!(poll_once(first.as_mut()), Poll::Ready(1));
+    assert_eq!(phaser.phase(), 1);
+    assert_eq!(phaser.arrived(), 0);
+}
+
+#[test]
+fn phases_repeat_across_threads() {
+    let phaser = Arc::new(Phaser::new(3));
+    let parties = (0..3)
+        .map(|_| {
+            let phaser = phaser.clone();
+            thread::spawn(move || {
+                (0..5)
+                    .map(|_| block_on_timeout(phaser.arrive_and_wait(), TIMEOUT))
+                    .collect::<Vec<_>>()
+            })
+        })
+        .collect::<Vec<_>>();
+    for party in parties {
+        assert_eq!(party.join().unwrap(), vec![1, 2, 3, 4, 5]);
+    }
+}
+
+#[test]
+fn register_adds_a_party() {
+    let phaser = Phaser::new(1);
+    assert_eq!(phaser.register(), 0);
+    assert_eq!(phaser.parties(), 2);
+
+    assert_eq!(phaser.arrive(), 0);
+    assert_eq!(phaser.phase(), 0);
+    assert_eq!(block_on_timeout(phaser.arrive_and_wait(), TIMEOUT), 1);
+}
+
+#[test]
+fn deregister_ends_the_phase() {
+    let phaser = Phaser::new(3);
+    let mut wait = pin!(phaser.arrive_and_wait());
+    assert!(poll_once(wait.as_mut()).is_pending());
+    assert_eq!(phaser.arrive_and_deregister(), 0);
+    assert!(poll_once(wait.as_mut()).is_pending());
+
+    assert_eq!(phaser.deregister(), 1);
+    assert_eq!(poll_once(wait.as_mut()), Poll::Ready(1));
+    assert_eq!(phaser.parties(), 1);
+}
+
+#[test]
+fn wait_for_advance_does_not_arrive() {
+    let phaser = Phaser::new(2);
+    let mut wait = pin!(phaser.wait_for_advance(0));
+    assert!(poll_once(wait.as_mut()).is_pending());
+    assert_eq!(phaser.arrived(), 0);
+
+    phaser.arrive();
+    assert!(poll_once(wait.as_mut()).is_pending());
+    phaser.arrive();
+    assert_eq!(poll_once(wait.as_mut()), Poll::Ready(1));
+    assert_eq!(block_on_timeout(phaser.wait_for_advance(0), TIMEOUT), 1);
+}
+
+#[test]
+fn dropped_wait_takes_back_its_arrival() {
+    let phaser = Phaser::new(2);
+    {
+        let mut wait = pin!(phaser.arrive_and_wait());
+        assert!(poll_once(wait.as_mut()).is_pending());
+        assert_eq!(phaser.arrived(), 1);
+    }
+    assert_eq!(phaser.arrived(), 0);
+
+    phaser.arrive();
+    assert_eq!(phaser.phase(), 0);
+}
+
+#[test]
+#[should_panic(expected = "more parties arrived than are registered")]
+fn arriving_without_parties_panics() {
+    Phaser::new(0).arrive();
+}
+
+#[test]
+#[should_panic(expected = "more parties arrived than are registered")]
+fn waiting_without_parties_panics() {
+    let phaser = Phaser::new(0);
+    let _ = poll_once(pin!(phaser.arrive_and_wait()));
+}
@@ -0,0 +1,104 @@
+mod common;
+
+use std::{
+    panic::{self, AssertUnwindSafe},
+    pin::pin,
+    sync::Arc,
+    task::Poll,
+    thread,
+    time::Duration,
+};
+
+use casus::{OwnedRwLockReadGuard, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use common::{block_on_timeout, poll_once};
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+#[test]
+fn readers_share_and_writers_exclude() {
+    let lock = RwLock::new(0);
+    let first = lock.try_read().unwrap();
+    let second = lock.try_read().unwrap();
+    assert!(lock.try_write().is_none());
+    drop((first, second));
+    let mut write = lock.try_write().unwrap();
+    *write += 1;
+    assert!(lock.try_read().is_none());
+    drop(write);
+    assert_eq!(*lock.try_read().unwrap(), 1);
+}
+
+#[test]
+fn waiting_writers_hold_up_new_readers() {
+    let lock = RwLock::new(0);
+    let read = lock.try_read().unwrap();
+    let mut write = pin!(lock.write());
+    assert!(poll_once(write.as_mut()).is_pending());
+    assert!(lock.try_read().is_none());
+    let mut later = pin!(lock.read());
+    assert!(poll_once(later.as_mut()).is_pending());
+    drop(read);
+    assert!(poll_once(later.as_mut()).is_pending());
+    match poll_once(write.as_mut()) {
+        Poll::Ready(guard) => drop(guard),
+        Poll::Pending => panic!("the writer should have been served first"),
+    }
+    assert!(poll_once(later.as_mut()).is_ready());
+}
+
+#[test]
+fn writes_are_seen_by_every_thread() {
+    let lock = Arc::new(RwLock::new(0));
+    let tasks = (0..4)
+        .map(|_| {
+            let lock = lock.clone();
+            thread::spawn(move || {
+                for _ in 0..100 {
+                    *block_on_timeout(lock.write(), TIMEOUT) += 1;
+                    let _ = *block_on_timeout(lock.read(), TIMEOUT);
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for task in tasks {
+        task.join().unwrap();
+    }
+    assert_eq!(*block_on_timeout(lock.read(), TIMEOUT), 400);
+}
+
+#[test]
+fn mapped_guards() {
+    let lock = Arc::new(RwLock::new((String::from("name"), 0)));
+    let mut count = RwLockWriteGuard::map(lock.try_write().unwrap(), |(_, count)| count);
+    *count += 1;
+    assert!(lock.try_read().is_none());
+    drop(count);
+    let name = RwLockReadGuard::map(lock.try_read().unwrap(), |(name, _)| name.as_str());
+    let owned = OwnedRwLockReadGuard::map(lock.clone().try_read_owned().unwrap(), |(_, c)| c);
+    assert_eq!((&*name, *owned), ("name", 1));
+    assert!(lock.try_write().is_none());
+    drop((name, owned));
+    assert!(lock.try_write().is_some());
+}
+
+#[test]
+fn panicking_maps_unlock_the_lock() {
+    let lock = Arc::new(RwLock::new(0));
+    let mapped = panic::catch_unwind(AssertUnwindSafe(|| {
+        RwLockReadGuard::map(lock.try_read().unwrap(), |_| -> &u32 { panic!("map") })
+    }));
+    assert!(mapped.is_err());
+    assert!(lock.try_write().is_some());
+    let mapped = panic::catch_unwind(AssertUnwindSafe(|| {
+        OwnedRwLockReadGuard::map(lock.clone().try_read_owned().unwrap(), |_| -> &u32 {
+            panic!("map")
+        })
+    }));
+    assert!(mapped.is_err());
+    assert!(lock.try_write().is_some());
+    let mapped = panic::catch_unwind(AssertUnwindSafe(|| {
+        RwLockWriteGuard::map(lock.try_write().unwrap(), |_| -> &mut u32 { panic!("map") })
+    }));
+    assert!(mapped.is_err());
+    assert!(lock.try_read().is_some());
+}
@@ -0,0 +1,115 @@
+mod common;
+
+use std::{
+    pin::pin,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    task::Poll,
+    thread,
+    time::Duration,
+};
+
+use casus::{Closed, Semaphore, TryAcquireError};
+use common::{block_on_timeout, poll_once};
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+#[test]
+fn permits_limit_concurrency() {
+    let semaphore = Arc::new(Semaphore::new(2));
+    let running = Arc::new(AtomicUsize::new(0));
+    let tasks = (0..8)
+        .map(|_| {
+            let semaphore = semaphore.clone();
+            let running = running.clone();
+            thread::spawn(move || {
+                for _ in 0..50 {
+                    let _permit = block_on_timeout(semaphore.acquire(1), TIMEOUT).unwrap();
+                    assert!(running.fetch_add(1, Ordering::SeqCst) < 2);
+                    thread::yield_now();
+                    running.fetch_sub(1, Ordering::SeqCst);
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for task in tasks {
+        task.join().unwrap();
+    }
+    assert_eq!(semaphore.available_permits(), 2);
+}
+
+#[test]
+fn large_acquires_are_not_starved() {
+    let semaphore = Semaphore::new(2);
+    let held = semaphore.try_acquire(1).unwrap();
+    let mut large = pin!(semaphore.acquire(2));
+    assert!(poll_once(large.as_mut()).is_pending());
+    // a small acquire would fit, but it has to wait behind the large one
+    assert_eq!(
+        semaphore.try_acquire(1).unwrap_err(),
+        TryAcquireError::NoPermits
+    );
+    let mut small = pin!(semaphore.acquire(1));
+    assert!(poll_once(small.as_mut()).is_pending());
+    drop(held);
+    let large = match poll_once(large.as_mut()) {
+        Poll::Ready(permit) => permit.unwrap(),
+        Poll::Pending => panic!("the large acquire should have been served first"),
+    };
+    assert_eq!(large.permits(), 2);
+    assert!(poll_once(small.as_mut()).is_pending());
+    drop(large);
+    assert!(poll_once(small.as_mut()).is_ready());
+}
+
+#[test]
+fn cancelled_acquires_return_their_permits() {
+    let semaphore = Semaphore::new(1);
+    let held = semaphore.try_acquire(1).unwrap();
+    let mut first = Box::pin(semaphore.acquire(1));
+    let mut second = pin!(semaphore.acquire(1));
+    assert!(poll_once(first.as_mut()).is_pending());
+    assert!(poll_once(second.as_mut()).is_pending());
+    drop(held);
+    // the permit was handed to the first acquire, dropping it passes it on to the second
+    drop(first);
+    assert!(poll_once(second.as_mut()).is_ready());
+}
+
+#[test]
+fn add_permits_and_forget() {
+    let semaphore = Semaphore::new(0);
+    let mut acquire = pin!(semaphore.acquire(3));
+    assert!(poll_once(acquire.as_mut()).is_pending());
+    semaphore.add_permits(3);
+    let Poll::Ready(Ok(permit)) = poll_once(acquire.as_mut()) else {
+        panic!("the acquire should have been served");
+    };
+    permit.forget();
+    assert_eq!(semaphore.available_permits(), 0);
+}
+
+#[test]
+fn close_fails_waiting_and_new_acquires() {
+    let semaphore = Arc::new(Semaphore::new(0));
+    let owned = semaphore.clone().try_acquire_owned(0).unwrap();
+    let waiting = {
+        let semaphore = semaphore.clone();
+        thread::spawn(move || block_on_timeout(semaphore.acquire_owned(1), TIMEOUT).map(drop))
+    };
+    thread::sleep(Duration::from_millis(20));
+    semaphore.close();
+    assert_eq!(waiting.join().unwrap(), Err(Closed));
+    assert!(semaphore.is_closed());
+    assert_eq!(
+        block_on_timeout(semaphore.acquire(1), TIMEOUT).map(drop),
+        Err(Closed)
+    );
+    assert_eq!(
+        semaphore.try_acquire(0).map(drop),
+        Err(TryAcquireError::Closed)
+    );
+    drop(owned);
+}
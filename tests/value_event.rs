@@ -0,0 +1,60 @@
+mod common;
+
+#[cfg(feature = "std")]
+use std::time::Instant;
+use std::{pin::pin, sync::Arc, task::Poll, thread, time::Duration};
+
+#[cfg(feature = "std")]
+use casus::TimedOut;
+use casus::ValueEvent;
+use common::{block_on_timeout, poll_once};
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+#[test]
+fn set_wakes_every_waiter_with_the_value() {
+    let event = Arc::new(ValueEvent::new());
+    let waiters = (0..4)
+        .map(|_| {
+            let event = event.clone();
+            thread::spawn(move || block_on_timeout(event.wait(), TIMEOUT))
+        })
+        .collect::<Vec<_>>();
+    thread::sleep(Duration::from_millis(20));
+    assert_eq!(event.set(String::from("config")), None);
+    for waiter in waiters {
+        assert_eq!(waiter.join().unwrap(), "config");
+    }
+}
+
+#[test]
+fn later_waits_return_the_value_until_cleared() {
+    let event = ValueEvent::new();
+    event.set(1);
+    assert_eq!(poll_once(pin!(event.wait())), Poll::Ready(1));
+    assert_eq!(event.set(2), Some(1));
+    assert_eq!(event.get(), Some(2));
+    assert_eq!(event.clear(), Some(2));
+    assert!(!event.is_set());
+    assert!(poll_once(pin!(event.wait())).is_pending());
+}
+
+#[test]
+fn dropped_waits_deregister() {
+    let event = ValueEvent::new();
+    let mut wait = Box::pin(event.wait());
+    assert!(poll_once(wait.as_mut()).is_pending());
+    drop(wait);
+    event.set(3);
+    assert_eq!(block_on_timeout(event.wait(), TIMEOUT), 3);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn wait_timeout_times_out_when_not_set() {
+    let event = ValueEvent::<u32>::new();
+    let start = Instant::now();
+    let result = block_on_timeout(event.wait_timeout(Duration::from_millis(50)), TIMEOUT);
+    assert_eq!(result, Err(TimedOut));
+    assert!(start.elapsed() >= Duration::from_millis(50));
+}
@@ -0,0 +1,89 @@
+mod common;
+
+use std::{pin::pin, sync::Arc, thread, time::Duration};
+
+use casus::{CountdownLatch, WaitGroup};
+use common::{block_on_timeout, poll_once};
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+#[test]
+fn wait_completes_when_every_guard_is_dropped() {
+    let group = WaitGroup::new();
+    block_on_timeout(group.wait(), TIMEOUT);
+
+    let first = group.add();
+    let second = group.add();
+    assert_eq!(group.count(), 2);
+
+    let mut wait = pin!(group.wait());
+    assert!(poll_once(wait.as_mut()).is_pending());
+    first.done();
+    assert!(poll_once(wait.as_mut()).is_pending());
+    drop(second);
+    assert!(poll_once(wait.as_mut()).is_ready());
+    assert_eq!(group.count(), 0);
+}
+
+#[test]
+fn owned_guards_wait_for_threads() {
+    let group = Arc::new(WaitGroup::new());
+    let workers = (0..4)
+        .map(|_| {
+            let guard = group.clone().add_owned();
+            thread::spawn(move || {
+                thread::sleep(Duration::from_millis(10));
+                drop(guard);
+            })
+        })
+        .collect::<Vec<_>>();
+    block_on_timeout(group.wait(), TIMEOUT);
+    assert_eq!(group.count(), 0);
+    for worker in workers {
+        worker.join().unwrap();
+    }
+}
+
+#[test]
+fn wait_group_is_reusable() {
+    let group = WaitGroup::new();
+    group.add().done();
+    block_on_timeout(group.wait(), TIMEOUT);
+
+    let guard = group.add();
+    let mut wait = pin!(group.wait());
+    assert!(poll_once(wait.as_mut()).is_pending());
+    drop(guard);
+    assert!(poll_once(wait.as_mut()).is_ready());
+}
+
+#[test]
+fn countdown_latch_wakes_every_waiter_at_zero() {
+    let latch = Arc::new(CountdownLatch::new(3));
+    let waiters = (0..4)
+        .map(|_| {
+            let latch = latch.clone();
+            thread::spawn(move || block_on_timeout(latch.wait(), TIMEOUT))
+        })
+        .collect::<Vec<_>>();
+    for _ in 0..3 {
+        thread::sleep(Duration::from_millis(5));
+        latch.count_down();
+    }
+    for waiter in waiters {
+        waiter.join().unwrap();
+    }
+    assert_eq!(latch.count(), 0);
+}
+
+#[test]
+fn countdown_latch_stays_at_zero() {
+    let latch = CountdownLatch::new(1);
+    latch.count_down();
+    latch.count_down();
+    assert_eq!(latch.count(), 0);
+    block_on_timeout(latch.wait(), TIMEOUT);
+
+    let latch = CountdownLatch::new(0);
+    block_on_timeout(latch.wait(), TIMEOUT);
+}
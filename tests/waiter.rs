@@ -0,0 +1,155 @@
+mod common;
+
+#[cfg(feature = "std")]
+use std::time::Instant;
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    task::{Context, Poll, Wake, Waker},
+    thread,
+    time::Duration,
+};
+
+#[cfg(feature = "std")]
+use casus::TimedOut;
+use casus::{Closed, SharedWaiter, Waiter};
+use common::{block_on_timeout, poll_once};
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+#[test]
+#[cfg(feature = "std")]
+fn wait_deadline_times_out_when_not_woken() {
+    let (_handle, mut waiter) = Waiter::<u32>::new();
+    let deadline = Instant::now() + Duration::from_millis(50);
+    let result = block_on_timeout(waiter.wait_deadline(deadline), TIMEOUT);
+    assert_eq!(result, Err(TimedOut));
+    assert!(Instant::now() >= deadline);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn wait_timeout_returns_the_value() {
+    let (handle, mut waiter) = Waiter::new();
+    let waking = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(20));
+        handle.wake(7).unwrap();
+    });
+    let result = block_on_timeout(waiter.wait_timeout(TIMEOUT), TIMEOUT * 2);
+    assert_eq!(result, Ok(Ok(7)));
+    waking.join().unwrap();
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn wait_blocking_returns_the_value() {
+    let (handle, mut waiter) = Waiter::new();
+    let waking = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(20));
+        handle.wake("done").unwrap();
+    });
+    assert_eq!(waiter.wait_blocking(), Ok("done"));
+    waking.join().unwrap();
+}
+
+#[test]
+fn every_awaiting_clone_is_woken() {
+    let (handle, waiter) = Waiter::new();
+    let mut first = waiter.clone();
+    let mut second = waiter.clone();
+    let woken = Arc::new(AtomicUsize::new(0));
+    let counter = Waker::from(Arc::new(CountingWaker(woken.clone())));
+    let mut cx = Context::from_waker(&counter);
+    assert!(Pin::new(&mut first).poll(&mut cx).is_pending());
+    assert!(Pin::new(&mut second).poll(&mut cx).is_pending());
+    handle.wake(1).unwrap();
+    assert_eq!(woken.load(Ordering::SeqCst), 2);
+    assert_eq!(poll_once(Pin::new(&mut first)), Poll::Ready(Ok(1)));
+    // the value was taken, so the clone that lost the race completes instead of waiting forever
+    assert_eq!(poll_once(Pin::new(&mut second)), Poll::Ready(Err(Closed)));
+}
+
+#[test]
+fn polling_after_completion_is_a_no_op() {
+    let (handle, mut waiter) = Waiter::new();
+    handle.wake(1).unwrap();
+    assert_eq!(poll_once(Pin::new(&mut waiter)), Poll::Ready(Ok(1)));
+    assert!(poll_once(Pin::new(&mut waiter)).is_pending());
+    assert_eq!(handle.wake(2), Err(2));
+    assert!(poll_once(Pin::new(&mut waiter)).is_pending());
+}
+
+#[test]
+fn only_the_first_wake_delivers_a_value() {
+    let (handle, waiter) = SharedWaiter::new();
+    let other = handle.clone();
+    handle.wake(1).unwrap();
+    assert_eq!(other.wake(2), Err(2));
+    assert_eq!(handle.wake(3), Err(3));
+    let mut first = waiter.clone();
+    let mut second = waiter;
+    assert_eq!(poll_once(Pin::new(&mut first)), Poll::Ready(Ok(1)));
+    assert_eq!(poll_once(Pin::new(&mut second)), Poll::Ready(Ok(1)));
+}
+
+#[test]
+fn shared_waiter_gives_every_clone_the_value() {
+    let (handle, waiter) = SharedWaiter::new();
+    let waiting = (0..4)
+        .map(|_| {
+            let waiter = waiter.clone();
+            thread::spawn(move || block_on_timeout(waiter, TIMEOUT))
+        })
+        .collect::<Vec<_>>();
+    thread::sleep(Duration::from_millis(20));
+    handle.wake(String::from("value")).unwrap();
+    for waiting in waiting {
+        assert_eq!(waiting.join().unwrap().as_deref(), Ok("value"));
+    }
+    assert_eq!(block_on_timeout(waiter, TIMEOUT).as_deref(), Ok("value"));
+}
+
+#[test]
+fn dropping_every_handle_closes_the_waiter() {
+    let (handle, mut waiter) = Waiter::<u32>::new();
+    let other = handle.clone();
+    assert!(poll_once(Pin::new(&mut waiter)).is_pending());
+    drop(handle);
+    assert!(poll_once(Pin::new(&mut waiter)).is_pending());
+    drop(other);
+    assert_eq!(poll_once(Pin::new(&mut waiter)), Poll::Ready(Err(Closed)));
+}
+
+#[test]
+fn waking_a_dropped_waiter_returns_the_value() {
+    let (handle, waiter) = Waiter::new();
+    let other = waiter.clone();
+    drop(waiter);
+    assert!(!handle.is_closed());
+    drop(other);
+    assert!(handle.is_closed());
+    assert_eq!(handle.wake(5), Err(5));
+}
+
+#[test]
+fn closed_returns_once_every_waiter_is_dropped() {
+    let (handle, waiter) = Waiter::<u32>::new();
+    let dropping = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(20));
+        drop(waiter);
+    });
+    block_on_timeout(handle.closed(), TIMEOUT);
+    dropping.join().unwrap();
+}
+
+struct CountingWaker(Arc<AtomicUsize>);
+
+impl Wake for CountingWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
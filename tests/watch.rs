@@ -0,0 +1,88 @@
+mod common;
+
+use std::{pin::pin, thread, time::Duration};
+
+use casus::Watch;
+use common::{block_on_timeout, poll_once};
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+#[test]
+fn changed_returns_values_sent_before_the_call() {
+    let mut watch = Watch::new(0);
+    let sender = watch.clone();
+    assert!(!watch.has_changed());
+    sender.send(1);
+    assert!(watch.has_changed());
+    assert_eq!(*block_on_timeout(watch.changed(), TIMEOUT), 1);
+    assert!(!watch.has_changed());
+    assert!(poll_once(pin!(watch.changed())).is_pending());
+}
+
+#[test]
+fn changed_wakes_on_send_from_another_thread() {
+    let mut watch = Watch::new(String::new());
+    let sender = watch.clone();
+    let sending = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(20));
+        sender.send(String::from("updated"));
+    });
+    assert_eq!(*block_on_timeout(watch.changed(), TIMEOUT), "updated");
+    sending.join().unwrap();
+}
+
+#[test]
+fn wait_until_checks_every_new_value() {
+    let mut watch = Watch::new(0);
+    let sender = watch.clone();
+    let sending = thread::spawn(move || {
+        for _ in 0..10 {
+            thread::sleep(Duration::from_millis(1));
+            sender.send_modify(|v| *v += 1);
+        }
+    });
+    assert!(*block_on_timeout(watch.wait_until(|v| *v >= 10), TIMEOUT) >= 10);
+    sending.join().unwrap();
+    assert!(!watch.has_changed());
+}
+
+#[test]
+fn wait_until_returns_immediately_when_already_satisfied() {
+    let mut watch = Watch::new(4);
+    assert_eq!(*watch.send(6), 4);
+    assert_eq!(*watch.borrow(), 6);
+    assert!(poll_once(pin!(watch.wait_until(|v| v % 2 == 0))).is_ready());
+    assert!(!watch.has_changed());
+}
+
+#[test]
+fn borrows_dont_block_each_other_or_sends() {
+    let watch = Watch::new(1);
+    let other = watch.clone();
+    let first = watch.borrow();
+    let second = other.borrow();
+    assert_eq!((*first, *second), (1, 1));
+    assert_eq!(*watch.send(2), 1);
+    other.send_modify(|v| *v += 1);
+    // borrows keep the value they were taken with
+    assert_eq!((*first, *second), (1, 1));
+    assert_eq!(*watch.borrow(), 3);
+}
+
+#[test]
+fn wait_until_runs_the_predicate_outside_the_lock() {
+    let mut watch = Watch::new(0);
+    let sender = watch.clone();
+    let value = block_on_timeout(
+        watch.wait_until(|v| {
+            // borrowing or sending from the predicate would deadlock if it ran under the lock
+            assert_eq!(*sender.borrow(), *v);
+            if *v < 3 {
+                sender.send(*v + 1);
+            }
+            *v == 3
+        }),
+        TIMEOUT,
+    );
+    assert_eq!(*value, 3);
+}